@@ -0,0 +1,66 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use game::math::{Rect, SpatialGrid};
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg32;
+
+// Half of each count are bullets and half enemies, as in run_physics
+const COUNTS: [usize; 3] = [100, 1_000, 10_000];
+const CELL_SIZE: f32 = 64.0;
+
+// The playfield grows with the count so density stays about what a busy wave has
+fn random_bounds(rng: &mut Pcg32, count: usize, size: f32) -> Vec<Rect> {
+    let extent = (count as f32).sqrt() * 40.0;
+    (0..count)
+        .map(|_| {
+            Rect::new(
+                rng.gen_range(0.0..extent),
+                rng.gen_range(0.0..extent),
+                size,
+                size,
+            )
+        })
+        .collect()
+}
+
+fn brute_force(bullets: &[Rect], enemies: &[Rect]) -> usize {
+    bullets
+        .iter()
+        .map(|bullet| enemies.iter().filter(|e| bullet.overlaps(e)).count())
+        .sum()
+}
+
+fn grid(bullets: &[Rect], enemies: &[Rect]) -> usize {
+    let grid = SpatialGrid::build(CELL_SIZE, enemies.iter().copied());
+    let mut candidates = vec![];
+    bullets
+        .iter()
+        .map(|bullet| {
+            grid.query(bullet, &mut candidates);
+            candidates
+                .iter()
+                .filter(|&&i| bullet.overlaps(&enemies[i]))
+                .count()
+        })
+        .sum()
+}
+
+fn broadphase(c: &mut Criterion) {
+    let mut group = c.benchmark_group("broadphase");
+    for count in COUNTS {
+        let mut rng = Pcg32::seed_from_u64(count as u64);
+        let bullets = random_bounds(&mut rng, count / 2, 8.0);
+        let enemies = random_bounds(&mut rng, count / 2, 32.0);
+        assert_eq!(brute_force(&bullets, &enemies), grid(&bullets, &enemies));
+
+        group.bench_with_input(BenchmarkId::new("brute_force", count), &count, |b, _| {
+            b.iter(|| brute_force(black_box(&bullets), black_box(&enemies)))
+        });
+        group.bench_with_input(BenchmarkId::new("grid", count), &count, |b, _| {
+            b.iter(|| grid(black_box(&bullets), black_box(&enemies)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, broadphase);
+criterion_main!(benches);
@@ -0,0 +1,306 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    caffeinated_gorilla::space::types::{GamepadButton, Key},
+    exports::caffeinated_gorilla::space::game_api::{GamepadInfo, KeyboardInfo},
+};
+
+/// Something the player does, which any of the keys bound to it can trigger
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Pause,
+    Confirm,
+    Back,
+}
+
+impl Action {
+    pub const ALL: [Action; 8] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Fire,
+        Action::Pause,
+        Action::Confirm,
+        Action::Back,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move Up",
+            Action::MoveDown => "Move Down",
+            Action::MoveLeft => "Move Left",
+            Action::MoveRight => "Move Right",
+            Action::Fire => "Fire",
+            Action::Pause => "Pause",
+            Action::Confirm => "Confirm",
+            Action::Back => "Back",
+        }
+    }
+
+    /// Whether the two are ever read at the same time, so can't share a key.
+    /// Fire is only used in play and Confirm and Back only in menus, while
+    /// movement and Pause are used in both since the pause screen is a menu.
+    pub fn conflicts_with(&self, other: Action) -> bool {
+        // Both leave the pause screen, so sharing a key is harmless
+        if matches!(
+            (self, other),
+            (Action::Pause, Action::Back) | (Action::Back, Action::Pause)
+        ) {
+            return false;
+        }
+        let in_play = |action: &Action| !matches!(action, Action::Confirm | Action::Back);
+        let in_menus = |action: &Action| !matches!(action, Action::Fire);
+        (in_play(self) && in_play(&other)) || (in_menus(self) && in_menus(&other))
+    }
+
+    /// Gamepad buttons aren't rebindable, every pad lays these out the same way
+    pub fn buttons(&self) -> &'static [GamepadButton] {
+        match self {
+            Action::MoveUp => &[GamepadButton::DpadUp],
+            Action::MoveDown => &[GamepadButton::DpadDown],
+            Action::MoveLeft => &[GamepadButton::DpadLeft],
+            Action::MoveRight => &[GamepadButton::DpadRight],
+            Action::Fire => &[GamepadButton::South, GamepadButton::RightBumper],
+            Action::Pause => &[GamepadButton::Start],
+            Action::Confirm => &[GamepadButton::South, GamepadButton::Start],
+            Action::Back => &[GamepadButton::East],
+        }
+    }
+}
+
+/// Ready made bindings to start from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ControlScheme {
+    Arrows,
+    Wasd,
+    /// Everything within reach of the arrow keys
+    RightHand,
+}
+
+impl ControlScheme {
+    pub const ALL: [ControlScheme; 3] = [
+        ControlScheme::Arrows,
+        ControlScheme::Wasd,
+        ControlScheme::RightHand,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ControlScheme::Arrows => "Arrow Keys",
+            ControlScheme::Wasd => "WASD",
+            ControlScheme::RightHand => "Right Hand",
+        }
+    }
+
+    pub fn bindings(&self) -> Bindings {
+        let arrows = [Key::Up, Key::Down, Key::Left, Key::Right];
+        let (movement, fire, pause) = match self {
+            ControlScheme::Arrows => (arrows, Key::Space, Key::Escape),
+            ControlScheme::Wasd => ([Key::W, Key::S, Key::A, Key::D], Key::Space, Key::Escape),
+            ControlScheme::RightHand => (arrows, Key::RightControl, Key::Delete),
+        };
+        let [up, down, left, right] = movement;
+        Bindings {
+            keys: BTreeMap::from([
+                (Action::MoveUp, vec![up]),
+                (Action::MoveDown, vec![down]),
+                (Action::MoveLeft, vec![left]),
+                (Action::MoveRight, vec![right]),
+                (Action::Fire, vec![fire]),
+                (Action::Pause, vec![pause]),
+                (Action::Confirm, vec![fire, Key::Enter, Key::KpEnter]),
+                (Action::Back, vec![pause]),
+            ]),
+        }
+    }
+}
+
+/// Which keys trigger each action. The first key for each is the one the
+/// bindings screen changes, any after it are extras like Enter for Confirm.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Bindings {
+    keys: BTreeMap<Action, Vec<Key>>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        ControlScheme::Arrows.bindings()
+    }
+}
+
+impl Bindings {
+    pub fn keys(&self, action: Action) -> &[Key] {
+        self.keys.get(&action).map_or(&[], |keys| keys)
+    }
+
+    /// Makes `key` the first key for `action`, keeping any extras.
+    /// An action that would clash with `action` over `key` is given
+    /// `action`'s old key in its place, so the two swap. If the old key
+    /// would clash there too it's dropped instead, unless that would
+    /// leave the action with no keys at all.
+    pub fn bind(&mut self, action: Action, key: Key) {
+        let keys = self.keys.entry(action).or_default();
+        let old = (!keys.is_empty()).then(|| keys.remove(0));
+        keys.retain(|k| *k != key);
+        keys.insert(0, key);
+
+        let clashing: Vec<_> = Action::ALL
+            .into_iter()
+            .filter(|other| {
+                *other != action && other.conflicts_with(action) && self.keys(*other).contains(&key)
+            })
+            .collect();
+        for other in clashing {
+            let swap = old.filter(|old| !self.is_used_with(other, *old));
+            let keys = self.keys.entry(other).or_default();
+            let index = keys.iter().position(|k| *k == key).unwrap();
+            match swap.or(old.filter(|_| keys.len() == 1)) {
+                Some(old) => keys[index] = old,
+                None => {
+                    keys.remove(index);
+                }
+            }
+        }
+    }
+
+    /// Whether `key` already triggers `action` or anything read at the same time
+    fn is_used_with(&self, action: Action, key: Key) -> bool {
+        self.keys.iter().any(|(other, keys)| {
+            (*other == action || other.conflicts_with(action)) && keys.contains(&key)
+        })
+    }
+
+    /// Actions missing from an older or hand edited file get their default keys,
+    /// so the game can't end up with no way to fire or pause
+    pub fn fill_missing(&mut self) {
+        for (action, keys) in Bindings::default().keys {
+            let bound = self.keys.entry(action).or_default();
+            if bound.is_empty() {
+                *bound = keys;
+            }
+        }
+    }
+
+    pub fn pressed(&self, action: Action, key: &KeyboardInfo, gamepad: &GamepadInfo) -> bool {
+        self.keys(action).iter().any(|k| key.pressed.contains(k))
+            || action.buttons().iter().any(|b| gamepad.pressed.contains(b))
+    }
+
+    pub fn down(&self, action: Action, key: &KeyboardInfo, gamepad: &GamepadInfo) -> bool {
+        self.keys(action).iter().any(|k| key.down.contains(k))
+            || action.buttons().iter().any(|b| gamepad.down.contains(b))
+    }
+
+    /// The keys for `action` as the player would read them, like "Up / W"
+    pub fn label(&self, action: Action) -> String {
+        let names: Vec<_> = self.keys(action).iter().map(key_name).collect();
+        names.join(" / ")
+    }
+}
+
+pub fn key_name(key: &Key) -> String {
+    // Debug gives `Key::KeyZero`, the digits read better without either prefix
+    let name = format!("{key:?}");
+    let name = name.trim_start_matches("Key::");
+    name.strip_prefix("Key").unwrap_or(name).to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn keys(pressed: Vec<Key>, down: Vec<Key>) -> KeyboardInfo {
+        KeyboardInfo {
+            pressed,
+            released: vec![],
+            down,
+        }
+    }
+
+    #[test]
+    fn rebound_actions_follow_new_key() {
+        let mut bindings = Bindings::default();
+        let gamepad = GamepadInfo::default();
+        assert!(bindings.down(Action::MoveUp, &keys(vec![], vec![Key::Up]), &gamepad));
+
+        bindings.bind(Action::MoveUp, Key::W);
+        assert!(!bindings.down(Action::MoveUp, &keys(vec![], vec![Key::Up]), &gamepad));
+        assert!(bindings.pressed(Action::MoveUp, &keys(vec![Key::W], vec![Key::W]), &gamepad));
+        assert_eq!("W", bindings.label(Action::MoveUp));
+
+        // Only the first key changes, Enter stays on Confirm
+        bindings.bind(Action::Confirm, Key::Z);
+        assert_eq!("Z / Enter / KpEnter", bindings.label(Action::Confirm));
+    }
+
+    #[test]
+    fn clashing_keys_swap() {
+        let mut bindings = Bindings::default();
+        bindings.bind(Action::Fire, Key::Z);
+        // Back is only read in menus, where Fire isn't, so they can share Z
+        bindings.bind(Action::Back, Key::Z);
+        assert_eq!("Z", bindings.label(Action::Fire));
+        assert_eq!("Z", bindings.label(Action::Back));
+        assert_eq!("Escape", bindings.label(Action::Pause));
+
+        // Enter is already one of Confirm's, so it moves to the front rather than doubling up
+        bindings.bind(Action::Confirm, Key::Enter);
+        assert_eq!("Enter / KpEnter", bindings.label(Action::Confirm));
+
+        // Menus read movement too, so Confirm takes Up in place of the key it lost
+        bindings.bind(Action::MoveUp, Key::KpEnter);
+        assert_eq!("KpEnter", bindings.label(Action::MoveUp));
+        assert_eq!("Enter / Up", bindings.label(Action::Confirm));
+    }
+
+    #[test]
+    fn pause_and_confirm_keys_swap() {
+        let mut bindings = Bindings::default();
+        // The pause screen reads both, and Escape is already Back's so Confirm just loses Enter
+        bindings.bind(Action::Pause, Key::Enter);
+        assert_eq!("Enter", bindings.label(Action::Pause));
+        assert_eq!("Space / KpEnter", bindings.label(Action::Confirm));
+
+        // Both Fire and Confirm had Space, and both take Enter in its place
+        bindings.bind(Action::Pause, Key::Space);
+        assert_eq!("Space", bindings.label(Action::Pause));
+        assert_eq!("Enter", bindings.label(Action::Fire));
+        assert_eq!("Enter / KpEnter", bindings.label(Action::Confirm));
+    }
+
+    #[test]
+    fn missing_bindings_get_defaults() {
+        let mut bindings: Bindings =
+            serde_json::from_str(r#"{"Fire": ["KeyZero"], "Pause": []}"#).unwrap();
+        bindings.fill_missing();
+        assert_eq!("Zero", bindings.label(Action::Fire));
+        assert_eq!("Escape", bindings.label(Action::Pause));
+        assert_eq!("Up", bindings.label(Action::MoveUp));
+    }
+
+    #[test]
+    fn gamepad_buttons_trigger_actions() {
+        let bindings = Bindings::default();
+        let gamepad = GamepadInfo {
+            connected: true,
+            pressed: vec![GamepadButton::South],
+            down: vec![GamepadButton::South, GamepadButton::DpadLeft],
+            ..Default::default()
+        };
+        let key = keys(vec![], vec![]);
+        assert!(bindings.pressed(Action::Fire, &key, &gamepad));
+        assert!(bindings.pressed(Action::Confirm, &key, &gamepad));
+        assert!(bindings.down(Action::MoveLeft, &key, &gamepad));
+        assert!(!bindings.down(Action::MoveRight, &key, &gamepad));
+        assert!(!bindings.pressed(Action::Back, &key, &gamepad));
+    }
+}
@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    math::{Position, Vec2},
+    state::{Bullet, FirePattern, Shape, ShapeKind},
+    ui::{Color, ORANGE, RED, YELLOW},
+    Screen,
+};
+
+// The first boss arrives at whichever of these comes first,
+// with each later encounter pushed back by the same amount
+const BOSS_SCORE_INTERVAL: u64 = 2000;
+const BOSS_TIME_INTERVAL: f32 = 150.0;
+
+const BOSS_SIZE: f32 = 120.0;
+const ENTRY_SPEED: f32 = 60.0;
+// How far down the screen the boss stops after entering
+const HOVER_Y: f32 = 140.0;
+
+pub struct BossPhase {
+    /// The phase begins once health drops to this fraction of the maximum
+    pub health_fraction: f32,
+    /// Volleys are fired in order, looping back to the start
+    pub patterns: &'static [FirePattern],
+    /// Seconds between volleys
+    pub fire_interval: f32,
+    /// Speed of the side-to-side sweep
+    pub sweep_speed: f32,
+    pub color: Color,
+}
+
+const PHASES: [BossPhase; 3] = [
+    BossPhase {
+        health_fraction: 1.0,
+        patterns: &[FirePattern::Spread { count: 5, arc: 0.8 }],
+        fire_interval: 1.4,
+        sweep_speed: 0.5,
+        color: YELLOW,
+    },
+    BossPhase {
+        health_fraction: 0.66,
+        patterns: &[
+            FirePattern::Ring { count: 12 },
+            FirePattern::Spread { count: 3, arc: 0.4 },
+        ],
+        fire_interval: 1.0,
+        sweep_speed: 0.9,
+        color: ORANGE,
+    },
+    BossPhase {
+        health_fraction: 0.33,
+        patterns: &[
+            FirePattern::Aimed,
+            FirePattern::Aimed,
+            FirePattern::Ring { count: 16 },
+        ],
+        fire_interval: 0.4,
+        sweep_speed: 1.4,
+        color: RED,
+    },
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Boss {
+    pub shape: Shape,
+    pub health: u32,
+    pub max_health: u32,
+    /// Which encounter this is, starting at 1. Later bosses are tougher.
+    pub encounter: u32,
+    /// Seconds since the boss appeared, drives the sweep
+    pub age: f32,
+    pub fire_cooldown: f32,
+    /// Index of the next pattern to fire in the current phase
+    pub next_pattern: usize,
+    /// Seconds remaining to flash after being hit
+    pub hit_flash: f32,
+}
+
+impl Boss {
+    pub fn new(screen: &Screen, encounter: u32) -> Self {
+        let max_health = 40 + 20 * encounter;
+        let position = Position {
+            x: screen.width() / 2.0,
+            y: -BOSS_SIZE,
+        };
+        Self {
+            shape: Shape {
+                previous_position: position,
+                position,
+                velocity: Vec2::DOWN * ENTRY_SPEED,
+                size: BOSS_SIZE,
+                color: PHASES[0].color.clone(),
+                kind: ShapeKind::Rect,
+            },
+            health: max_health,
+            max_health,
+            encounter,
+            age: 0.0,
+            fire_cooldown: PHASES[0].fire_interval,
+            next_pattern: 0,
+            hit_flash: 0.0,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        format!("Dreadnought Mk. {}", self.encounter)
+    }
+
+    pub fn health_fraction(&self) -> f32 {
+        self.health as f32 / self.max_health as f32
+    }
+
+    pub fn phase_index(&self) -> usize {
+        let fraction = self.health_fraction();
+        PHASES
+            .iter()
+            .rposition(|phase| fraction <= phase.health_fraction)
+            .unwrap_or(0)
+    }
+
+    pub fn phase(&self) -> &'static BossPhase {
+        &PHASES[self.phase_index()]
+    }
+
+    pub fn phase_thresholds() -> impl Iterator<Item = f32> {
+        PHASES.iter().skip(1).map(|phase| phase.health_fraction)
+    }
+
+    pub fn is_entering(&self) -> bool {
+        self.shape.position.y < HOVER_Y
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.health == 0
+    }
+
+    pub fn reward(&self) -> u64 {
+        2500 * self.encounter as u64
+    }
+
+    pub fn damage(&mut self, amount: u32) {
+        let phase = self.phase_index();
+        self.health = self.health.saturating_sub(amount);
+        self.hit_flash = 0.1;
+
+        if phase != self.phase_index() {
+            self.next_pattern = 0;
+            self.shape.color = self.phase().color.clone();
+        }
+    }
+
+    /// Moves the boss and returns any bullets fired this frame
+    pub fn update(&mut self, screen: &Screen, frame_time: f32, target: &Position) -> Vec<Bullet> {
+        self.hit_flash = (self.hit_flash - frame_time).max(0.0);
+
+        if self.is_entering() {
+            self.shape.advance(frame_time);
+            return vec![];
+        }
+
+        let phase = self.phase();
+        self.age += frame_time;
+        let sweep_width = (screen.width() - self.shape.size) / 2.0;
+        self.shape.position.x =
+            screen.width() / 2.0 + sweep_width * (self.age * phase.sweep_speed).sin();
+
+        self.fire_cooldown -= frame_time;
+        if self.fire_cooldown > 0.0 {
+            return vec![];
+        }
+        self.fire_cooldown = phase.fire_interval;
+
+        let pattern = &phase.patterns[self.next_pattern % phase.patterns.len()];
+        self.next_pattern += 1;
+        pattern
+            .directions(&self.shape.position, target)
+            .into_iter()
+            .map(|direction| Bullet::new_enemy(&self.shape, direction))
+            .collect()
+    }
+}
+
+/// Tracks when the next boss is due
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BossSchedule {
+    pub encounters: u32,
+    pub next_score: u64,
+    pub next_time: f32,
+    /// Seconds spent in play this game
+    pub play_time: f32,
+}
+
+impl Default for BossSchedule {
+    fn default() -> Self {
+        Self {
+            encounters: 0,
+            next_score: BOSS_SCORE_INTERVAL,
+            next_time: BOSS_TIME_INTERVAL,
+            play_time: 0.0,
+        }
+    }
+}
+
+impl BossSchedule {
+    pub fn is_due(&self, score: u64) -> bool {
+        score >= self.next_score || self.play_time >= self.next_time
+    }
+
+    pub fn start_encounter(&mut self) -> u32 {
+        self.encounters += 1;
+        self.encounters
+    }
+
+    pub fn defeated(&mut self, score: u64) {
+        self.next_score = score + BOSS_SCORE_INTERVAL;
+        self.next_time = self.play_time + BOSS_TIME_INTERVAL;
+    }
+}
@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    math::Position,
+    ui::{Color, WHITE, YELLOW},
+};
+
+// Seconds after a kill for the next one to keep the chain going
+const COMBO_WINDOW: f32 = 2.0;
+// Kills needed in a chain to raise the multiplier by one
+const KILLS_PER_STEP: u32 = 2;
+const MAX_MULTIPLIER: u32 = 8;
+
+const POPUP_LIFETIME: f32 = 1.0;
+const POPUP_RISE_SPEED: f32 = 40.0;
+
+/// A chain of kills, each landing within `COMBO_WINDOW` of the last
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Combo {
+    kills: u32,
+    remaining: f32,
+}
+
+impl Combo {
+    pub fn multiplier(&self) -> u32 {
+        if self.kills == 0 {
+            return 1;
+        }
+        (1 + (self.kills - 1) / KILLS_PER_STEP).min(MAX_MULTIPLIER)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.kills > 0
+    }
+
+    /// How much of the window is left before the chain breaks, from 0 to 1
+    pub fn remaining_fraction(&self) -> f32 {
+        (self.remaining / COMBO_WINDOW).clamp(0.0, 1.0)
+    }
+
+    /// Extends the chain, returning the multiplier the kill scored at
+    pub fn register_kill(&mut self) -> u32 {
+        self.kills += 1;
+        self.remaining = COMBO_WINDOW;
+        self.multiplier()
+    }
+
+    pub fn reset(&mut self) {
+        *self = Combo::default();
+    }
+
+    pub fn update(&mut self, frame_time: f32) {
+        if self.is_active() {
+            self.remaining -= frame_time;
+            if self.remaining <= 0.0 {
+                self.reset();
+            }
+        }
+    }
+}
+
+/// Floating "+N x3" text where points were scored
+#[derive(Debug, Clone)]
+pub struct ScorePopup {
+    pub position: Position,
+    pub text: String,
+    pub color: Color,
+    age: f32,
+}
+
+impl ScorePopup {
+    pub fn new(position: Position, points: u64, multiplier: u32) -> Self {
+        let (text, color) = if multiplier > 1 {
+            (format!("+{points} x{multiplier}"), YELLOW)
+        } else {
+            (format!("+{points}"), WHITE)
+        };
+        Self {
+            position,
+            text,
+            color,
+            age: 0.0,
+        }
+    }
+
+    /// Drifts up and fades, returning false once it has disappeared
+    pub fn update(&mut self, frame_time: f32) -> bool {
+        self.age += frame_time;
+        self.position.y -= POPUP_RISE_SPEED * frame_time;
+        self.color.a = 1.0 - (self.age / POPUP_LIFETIME).min(1.0);
+        self.age < POPUP_LIFETIME
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn combo_multiplier_and_window() {
+        let mut combo = Combo::default();
+        let multipliers: Vec<u32> = (0..5).map(|_| combo.register_kill()).collect();
+        assert_eq!(vec![1, 1, 2, 2, 3], multipliers);
+
+        combo.update(COMBO_WINDOW / 2.0);
+        assert_eq!(3, combo.multiplier());
+        combo.update(COMBO_WINDOW);
+        assert!(!combo.is_active());
+        assert_eq!(1, combo.register_kill());
+    }
+}
@@ -0,0 +1,95 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    state::ShapeKind,
+    ui::{Color, GRAY, GREEN, ORANGE, PURPLE},
+};
+
+/// The kinds of enemy, each with its own toughness, speed, look and value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EnemyKind {
+    /// The original enemy, any size and color, worth its size in points
+    #[default]
+    Grunt,
+    /// Large and slow, taking several hits to bring down
+    Tank,
+    /// Small and fast
+    Scout,
+    /// A drifting circle, dangerous to touch
+    Mine,
+    /// Breaks into scouts when destroyed
+    Splitter,
+}
+
+impl EnemyKind {
+    /// Hits needed to destroy it
+    pub fn health(&self) -> u32 {
+        match self {
+            EnemyKind::Grunt => 1,
+            EnemyKind::Tank => 5,
+            EnemyKind::Scout => 1,
+            EnemyKind::Mine => 2,
+            EnemyKind::Splitter => 2,
+        }
+    }
+
+    pub fn size_range(&self) -> Range<f32> {
+        match self {
+            EnemyKind::Grunt => 16.0..64.0,
+            EnemyKind::Tank => 56.0..72.0,
+            EnemyKind::Scout => 14.0..20.0,
+            EnemyKind::Mine => 24.0..32.0,
+            EnemyKind::Splitter => 40.0..48.0,
+        }
+    }
+
+    pub fn speed_range(&self) -> Range<f32> {
+        match self {
+            EnemyKind::Grunt => 50.0..150.0,
+            EnemyKind::Tank => 30.0..60.0,
+            EnemyKind::Scout => 180.0..260.0,
+            EnemyKind::Mine => 40.0..70.0,
+            EnemyKind::Splitter => 60.0..90.0,
+        }
+    }
+
+    /// Grunts pick a random color instead
+    pub fn color(&self) -> Option<Color> {
+        match self {
+            EnemyKind::Grunt => None,
+            EnemyKind::Tank => Some(GRAY),
+            EnemyKind::Scout => Some(GREEN),
+            EnemyKind::Mine => Some(PURPLE),
+            EnemyKind::Splitter => Some(ORANGE),
+        }
+    }
+
+    pub fn shape(&self) -> ShapeKind {
+        match self {
+            EnemyKind::Mine => ShapeKind::Circle,
+            EnemyKind::Scout => ShapeKind::triangle(),
+            _ => ShapeKind::Rect,
+        }
+    }
+
+    /// Points for destroying an enemy of this kind and `size`
+    pub fn score(&self, size: f32) -> u64 {
+        match self {
+            EnemyKind::Grunt => size.round() as u64,
+            EnemyKind::Tank => 150,
+            EnemyKind::Scout => 40,
+            EnemyKind::Mine => 60,
+            EnemyKind::Splitter => 80,
+        }
+    }
+
+    /// What the enemy breaks into when destroyed, and how many
+    pub fn splits_into(&self) -> Option<(EnemyKind, u32)> {
+        match self {
+            EnemyKind::Splitter => Some((EnemyKind::Scout, 2)),
+            _ => None,
+        }
+    }
+}
@@ -1,27 +1,102 @@
 use std::sync::{Arc, Mutex};
 
 use crate::{
+    bindings::{Action, Bindings, ControlScheme},
+    boss::Boss,
     caffeinated_gorilla::space::types::{Key, Size},
-    exports::caffeinated_gorilla::space::game_api::{KeyboardInfo, MouseInfo},
+    combo::ScorePopup,
+    exports::caffeinated_gorilla::space::game_api::{GamepadInfo, KeyboardInfo, MouseInfo},
     infrastructure::{Screen, Shader},
+    math::{Collider, SpatialGrid, Vec2},
+    particles::{EXPLOSION, IMPACT},
+    powerups::{PowerUp, PowerUpKind},
+    score::{current_date, HighScore, INITIALS_LENGTH},
+    settings::{Difficulty, Settings, HUD_SCALE_STEP, MAX_HUD_SCALE, MIN_HUD_SCALE},
     shaders::load_shader,
-    state::{Bullet, GamePhase, GameState, Shape, MOVEMENT_SPEED},
-    ui::{ScreenExt, TextSize, RED, WHITE, YELLOW},
+    state::{Bullet, FirePattern, GamePhase, GameState, Shape, ShapeKind, Ship},
+    stats::RunSummary,
+    timestep::{FixedTimestep, PendingInput},
+    tuning::Tuning,
+    ui::{
+        Camera, Color, ScreenExt, TextSize, Transform, Ui, WidgetState, AQUA, PANEL, RED, WHITE,
+        YELLOW,
+    },
 };
 
+use rand::{seq::SliceRandom, Rng};
+
+// Chance a killed enemy drops a power-up
+const POWERUP_DROP_CHANCE: f64 = 0.1;
+// Around the size of the largest enemies
+const BROADPHASE_CELL_SIZE: f32 = 64.0;
+// Piercing rounds are drawn and collide as streaks this many times their width
+const PIERCING_LENGTH: f32 = 3.0;
+// Screen shake trauma from destroying an enemy that takes more than one hit
+const TOUGH_KILL_TRAUMA: f32 = 0.25;
+
 pub struct Game {
     state: Arc<Mutex<GameState>>,
+    timestep: Mutex<FixedTimestep>,
+    input: Mutex<PendingInput>,
     shader: Box<Shader>,
 }
 
 impl Game {
+    /// High scores and settings are loaded from disk
     pub fn new(screen: &Screen) -> Game {
+        let state = GameState::new(screen, HighScore::load(), Settings::load());
+        Self::with_state(screen, state)
+    }
+
+    /// Nothing is read from or written to disk, for tests and headless runs
+    pub fn in_memory(screen: &Screen) -> Game {
+        let state = GameState::new(screen, HighScore::in_memory(), Settings::in_memory());
+        Self::with_state(screen, state)
+    }
+
+    fn with_state(screen: &Screen, state: GameState) -> Game {
         Self {
-            state: Arc::new(Mutex::new(GameState::new(screen))),
+            state: Arc::new(Mutex::new(state)),
+            timestep: Mutex::new(FixedTimestep::default()),
+            input: Mutex::new(PendingInput::default()),
             shader: Box::new(load_shader(screen)),
         }
     }
 
+    /// Sets how many simulation steps run per second of frame time
+    pub fn set_simulation_rate(&self, rate: f32) {
+        *self.timestep.lock().unwrap() = FixedTimestep::new(rate);
+    }
+
+    /// Skips the menu and begins a game with the given seed and tuning.
+    /// Scores and settings stay in memory, so headless runs leave the files alone.
+    pub fn start_simulation(&self, screen: &Screen, seed: u64, tuning: Tuning) {
+        let mut state = self.state.lock().unwrap();
+        *state =
+            GameState::new_with_seed(screen, seed, HighScore::in_memory(), Settings::in_memory());
+        state.phase = GamePhase::Playing;
+        state.tuning = tuning;
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        matches!(
+            self.state.lock().unwrap().phase,
+            GamePhase::EnterInitials | GamePhase::GameOver
+        )
+    }
+
+    pub fn run_summary(&self) -> RunSummary {
+        let state = self.state.lock().unwrap();
+        RunSummary {
+            seed: state.seed,
+            score: state.score.current_score(),
+            wave: state.waves.wave_number,
+            frames_survived: state.stats.frames_survived,
+            enemies_killed: state.stats.enemies_killed,
+            cause_of_death: state.stats.cause_of_death,
+        }
+    }
+
     pub fn save(&self) -> Vec<u8> {
         let save = if cfg!(debug_assertions) {
             serde_json::to_string_pretty(&*self.state.lock().unwrap())
@@ -39,210 +114,1396 @@ impl Game {
 
     pub fn update_frame(
         &self,
-        _mouse: MouseInfo,
+        mouse: MouseInfo,
         key: KeyboardInfo,
+        gamepad: GamepadInfo,
         screen: &Screen,
         frame_time: f32,
     ) {
         let mut state = self.state.lock().unwrap();
+        let mut timestep = self.timestep.lock().unwrap();
+        let mut input = self.input.lock().unwrap();
 
-        if !matches!(state.phase, GamePhase::Paused) {
-            state.update_frame += 1;
-            state.add_enemy(screen);
+        input.add(mouse, key, gamepad);
+        for _ in 0..timestep.advance(frame_time) {
+            let (mouse, key, gamepad) = input.take();
+            step(&mut state, mouse, key, gamepad, screen, timestep.step());
         }
+    }
 
-        match state.phase {
-            GamePhase::MainMenu => {
-                if key.pressed.contains(&Key::Space) {
-                    *state = GameState::new(screen);
-                    state.phase = GamePhase::Playing;
-                }
-            }
-            GamePhase::Playing => {
-                process_movement(&mut state, &key, screen, frame_time);
-                process_shoot(&mut state, &key);
-                if key.pressed.contains(&Key::Escape) {
-                    state.phase = GamePhase::Paused;
-                }
+    pub fn render_frame(&self, screen: &Screen) {
+        let alpha = self.timestep.lock().unwrap().alpha();
+        let mut state = self.state.lock().unwrap();
+        if state.settings.starfield {
+            self.shader.render(state.starfield_direction_modifier);
+        }
+        draw(&mut state, &mut Camera::new(screen), alpha);
+    }
+}
+
+fn step(
+    state: &mut GameState,
+    mouse: MouseInfo,
+    key: KeyboardInfo,
+    gamepad: GamepadInfo,
+    screen: &Screen,
+    frame_time: f32,
+) {
+    state.store_previous_positions();
+    let phase = std::mem::discriminant(&state.phase);
+
+    if !matches!(state.phase, GamePhase::Paused) {
+        state.update_frame += 1;
+        state.time += frame_time;
+        state.shake.update(frame_time);
+        state.spawn_enemies(screen, frame_time);
+    }
+
+    match state.phase {
+        GamePhase::MainMenu => {
+            process_main_menu_input(state, &mouse, &key, &gamepad, screen);
+        }
+        GamePhase::Playing => {
+            state.stats.frames_survived += 1;
+            state.update_player_timers(frame_time);
+            if !state.player.is_respawning() {
+                process_movement(state, &key, &gamepad, screen, frame_time);
+                process_shoot(state, &key, &gamepad);
+
+                let exhaust =
+                    state.player.shape.position + Vec2::DOWN * state.player.shape.half_width();
+                state.particles.emit_exhaust(&exhaust, frame_time);
             }
-            GamePhase::Paused => {
-                if key.pressed.contains(&Key::Space) {
-                    state.phase = GamePhase::Playing;
-                }
+            process_enemy_fire(state);
+            state.update_boss(screen, frame_time);
+            if state
+                .settings
+                .bindings
+                .pressed(Action::Pause, &key, &gamepad)
+            {
+                state.phase = GamePhase::Paused;
             }
-            GamePhase::GameOver => {
-                process_new_game_input(&mut state, &key, screen);
+        }
+        GamePhase::Paused => {
+            process_pause_input(state, &mouse, &key, &gamepad, screen);
+        }
+        GamePhase::Options => {
+            process_options_input(state, &mouse, &key, &gamepad, screen);
+        }
+        GamePhase::Bindings => {
+            process_bindings_input(state, &mouse, &key, &gamepad, screen);
+        }
+        GamePhase::Leaderboard => {
+            let bindings = &state.settings.bindings;
+            if bindings.pressed(Action::Confirm, &key, &gamepad)
+                || bindings.pressed(Action::Back, &key, &gamepad)
+            {
+                state.phase = GamePhase::MainMenu;
             }
         }
+        GamePhase::EnterInitials => {
+            process_initials_input(state, &key, &gamepad);
+        }
+        GamePhase::GameOver => {
+            process_game_over_input(state, &mouse, &key, &gamepad, screen);
+        }
+    }
+
+    // Each screen starts with focus on its first widget
+    if std::mem::discriminant(&state.phase) != phase {
+        state.widgets = WidgetState::default();
+    }
+
+    if !matches!(state.phase, GamePhase::Paused) {
+        run_physics(state, screen, frame_time);
+    }
+}
 
-        if !matches!(state.phase, GamePhase::Paused) {
-            run_physics(&mut state, screen, frame_time);
+fn process_main_menu_input(
+    state: &mut GameState,
+    mouse: &MouseInfo,
+    key: &KeyboardInfo,
+    gamepad: &GamepadInfo,
+    screen: &Screen,
+) {
+    // u64 seeds can be up to 20 digits, stop at 19 so any entry is valid
+    for digit in key.pressed.iter().filter_map(digit_for_key) {
+        if state.seed_entry.len() < 19 {
+            state.seed_entry.push(digit);
         }
     }
+    if key.pressed.contains(&Key::Backspace) {
+        state.seed_entry.pop();
+    }
 
-    pub fn render_frame(&self, screen: &Screen) {
-        let mut state = self.state.lock().unwrap();
-        self.shader.render(state.starfield_direction_modifier);
-        draw(&mut state, screen);
+    match run_menu(state, mouse, key, gamepad, screen, main_menu) {
+        Some(MenuAction::Start) => {
+            let seed = state.seed_entry.parse().ok();
+            start_game(state, screen, seed);
+        }
+        Some(MenuAction::HighScores) => state.phase = GamePhase::Leaderboard,
+        Some(MenuAction::Options) => state.phase = GamePhase::Options,
+        _ => {}
+    }
+}
+
+fn process_options_input(
+    state: &mut GameState,
+    mouse: &MouseInfo,
+    key: &KeyboardInfo,
+    gamepad: &GamepadInfo,
+    screen: &Screen,
+) {
+    if state.settings.bindings.pressed(Action::Back, key, gamepad) {
+        state.phase = GamePhase::MainMenu;
+        return;
+    }
+
+    let bindings = state.settings.bindings.clone();
+    let mut ui = Ui::input(&mut state.widgets, mouse, key, gamepad, &bindings);
+    match options_menu(&mut ui, &mut state.settings, screen.width()) {
+        Some(MenuAction::SettingsChanged) => state.settings.save(),
+        Some(MenuAction::Bindings) => state.phase = GamePhase::Bindings,
+        Some(MenuAction::MainMenu) => state.phase = GamePhase::MainMenu,
+        _ => {}
+    }
+}
+
+fn process_bindings_input(
+    state: &mut GameState,
+    mouse: &MouseInfo,
+    key: &KeyboardInfo,
+    gamepad: &GamepadInfo,
+    screen: &Screen,
+) {
+    // While waiting, the next key pressed is bound and a click cancels.
+    // Any key can be bound, so the keyboard can't be used to cancel.
+    if let Some(action) = state.rebinding {
+        if let Some(&pressed) = key.pressed.first() {
+            state.settings.bindings.bind(action, pressed);
+            state.settings.save();
+            state.rebinding = None;
+        } else if mouse.left.pressed {
+            state.rebinding = None;
+        }
+        return;
+    }
+
+    if state.settings.bindings.pressed(Action::Back, key, gamepad) {
+        state.phase = GamePhase::Options;
+        return;
+    }
+
+    let bindings = state.settings.bindings.clone();
+    let mut ui = Ui::input(&mut state.widgets, mouse, key, gamepad, &bindings);
+    match bindings_menu(&mut ui, &bindings, None, screen.width()) {
+        Some(MenuAction::Rebind(action)) => state.rebinding = Some(action),
+        Some(MenuAction::Preset(scheme)) => {
+            state.settings.bindings = scheme.bindings();
+            state.settings.save();
+        }
+        Some(MenuAction::Back) => state.phase = GamePhase::Options,
+        _ => {}
+    }
+}
+
+fn process_pause_input(
+    state: &mut GameState,
+    mouse: &MouseInfo,
+    key: &KeyboardInfo,
+    gamepad: &GamepadInfo,
+    screen: &Screen,
+) {
+    let bindings = &state.settings.bindings;
+    if bindings.pressed(Action::Pause, key, gamepad) || bindings.pressed(Action::Back, key, gamepad)
+    {
+        state.phase = GamePhase::Playing;
+        return;
+    }
+
+    match run_menu(state, mouse, key, gamepad, screen, pause_menu) {
+        Some(MenuAction::Resume) => state.phase = GamePhase::Playing,
+        Some(MenuAction::MainMenu) => *state = state.restart(screen, None),
+        _ => {}
+    }
+}
+
+fn process_initials_input(state: &mut GameState, key: &KeyboardInfo, gamepad: &GamepadInfo) {
+    for letter in key.pressed.iter().filter_map(letter_for_key) {
+        if state.initials_entry.len() < INITIALS_LENGTH {
+            state.initials_entry.push(letter);
+        }
+    }
+    if key.pressed.contains(&Key::Backspace) {
+        state.initials_entry.pop();
+    }
+
+    let confirmed = state
+        .settings
+        .bindings
+        .pressed(Action::Confirm, key, gamepad);
+    if confirmed && !state.initials_entry.is_empty() {
+        let wave = state.waves.wave_number;
+        state.placed_rank = state
+            .score
+            .record(&state.initials_entry, wave, current_date());
+        state.phase = GamePhase::GameOver;
+    }
+}
+
+fn process_game_over_input(
+    state: &mut GameState,
+    mouse: &MouseInfo,
+    key: &KeyboardInfo,
+    gamepad: &GamepadInfo,
+    screen: &Screen,
+) {
+    match run_menu(state, mouse, key, gamepad, screen, game_over_menu) {
+        Some(MenuAction::Retry) => {
+            let seed = state.seed;
+            start_game(state, screen, Some(seed));
+        }
+        Some(MenuAction::MainMenu) => *state = state.restart(screen, None),
+        _ => {}
+    }
+}
+
+enum MenuAction {
+    Start,
+    HighScores,
+    Options,
+    SettingsChanged,
+    Bindings,
+    Rebind(Action),
+    Preset(ControlScheme),
+    Back,
+    Resume,
+    Retry,
+    MainMenu,
+}
+
+// A menu lays out its widgets for a screen of the given size and returns what was chosen.
+// It runs once from `step` to handle input and again from `draw` to draw it.
+type Menu = fn(&mut Ui, f32, f32) -> Option<MenuAction>;
+
+fn run_menu(
+    state: &mut GameState,
+    mouse: &MouseInfo,
+    key: &KeyboardInfo,
+    gamepad: &GamepadInfo,
+    screen: &Screen,
+    menu: Menu,
+) -> Option<MenuAction> {
+    let mut ui = Ui::input(
+        &mut state.widgets,
+        mouse,
+        key,
+        gamepad,
+        &state.settings.bindings,
+    );
+    menu(&mut ui, screen.width(), screen.height())
+}
+
+fn draw_menu(widgets: &mut WidgetState, camera: &Camera, menu: Menu) {
+    let mut ui = Ui::draw(widgets, camera);
+    menu(&mut ui, camera.width(), camera.height());
+}
+
+fn main_menu(ui: &mut Ui, width: f32, height: f32) -> Option<MenuAction> {
+    ui.column(width / 2.0, height / 2.0 - 20.0);
+    let start = ui.button("Start Game");
+    let high_scores = ui.button("High Scores");
+    let options = ui.button("Options");
+
+    if start {
+        Some(MenuAction::Start)
+    } else if high_scores {
+        Some(MenuAction::HighScores)
+    } else if options {
+        Some(MenuAction::Options)
+    } else {
+        None
+    }
+}
+
+fn options_menu(ui: &mut Ui, settings: &mut Settings, width: f32) -> Option<MenuAction> {
+    ui.column(width / 2.0, 90.0);
+    let mut changed = false;
+
+    ui.label("Difficulty");
+    let labels = Difficulty::ALL.map(|d| d.label());
+    let mut selected = Difficulty::ALL
+        .iter()
+        .position(|d| *d == settings.difficulty)
+        .unwrap_or_default();
+    if ui.list(&labels, &mut selected) {
+        settings.difficulty = Difficulty::ALL[selected];
+        changed = true;
+    }
+
+    changed |= ui.toggle("Starfield", &mut settings.starfield);
+    changed |= ui.slider(
+        &format!("HUD Scale: {:.2}x", settings.hud_scale),
+        &mut settings.hud_scale,
+        MIN_HUD_SCALE,
+        MAX_HUD_SCALE,
+        HUD_SCALE_STEP,
+    );
+    let key_bindings = ui.button("Key Bindings");
+    let back = ui.button("Back");
+
+    if back {
+        Some(MenuAction::MainMenu)
+    } else if key_bindings {
+        Some(MenuAction::Bindings)
+    } else if changed {
+        Some(MenuAction::SettingsChanged)
+    } else {
+        None
+    }
+}
+
+// Actions down the left, with the presets and the way back on the right.
+// `rebinding` is the action waiting on a key, if any.
+fn bindings_menu(
+    ui: &mut Ui,
+    bindings: &Bindings,
+    rebinding: Option<Action>,
+    width: f32,
+) -> Option<MenuAction> {
+    let mut chosen = None;
+
+    ui.column(width / 2.0 - 140.0, 100.0);
+    for action in Action::ALL {
+        let keys = if rebinding == Some(action) {
+            "Press a Key".to_string()
+        } else {
+            bindings.label(action)
+        };
+        if ui.button(&format!("{}: {keys}", action.label())) {
+            chosen = Some(MenuAction::Rebind(action));
+        }
+    }
+
+    ui.column(width / 2.0 + 140.0, 100.0);
+    ui.label("Presets");
+    for scheme in ControlScheme::ALL {
+        if ui.button(scheme.label()) {
+            chosen = Some(MenuAction::Preset(scheme));
+        }
+    }
+    if ui.button("Back") {
+        chosen = Some(MenuAction::Back);
+    }
+    chosen
+}
+
+fn pause_menu(ui: &mut Ui, width: f32, height: f32) -> Option<MenuAction> {
+    ui.column(width / 2.0, height / 2.0 - 20.0);
+    let resume = ui.button("Resume");
+    let main_menu = ui.button("Quit to Menu");
+
+    if resume {
+        Some(MenuAction::Resume)
+    } else if main_menu {
+        Some(MenuAction::MainMenu)
+    } else {
+        None
+    }
+}
+
+fn game_over_menu(ui: &mut Ui, width: f32, height: f32) -> Option<MenuAction> {
+    ui.column(width / 2.0, height / 2.0 + 60.0);
+    let retry = ui.button("Retry Seed");
+    let main_menu = ui.button("Main Menu");
+
+    if retry {
+        Some(MenuAction::Retry)
+    } else if main_menu {
+        Some(MenuAction::MainMenu)
+    } else {
+        None
+    }
+}
+
+fn start_game(state: &mut GameState, screen: &Screen, seed: Option<u64>) {
+    *state = state.restart(screen, seed);
+    state.phase = GamePhase::Playing;
+}
+
+fn letter_for_key(key: &Key) -> Option<char> {
+    const LETTERS: [Key; 26] = [
+        Key::A,
+        Key::B,
+        Key::C,
+        Key::D,
+        Key::E,
+        Key::F,
+        Key::G,
+        Key::H,
+        Key::I,
+        Key::J,
+        Key::K,
+        Key::L,
+        Key::M,
+        Key::N,
+        Key::O,
+        Key::P,
+        Key::Q,
+        Key::R,
+        Key::S,
+        Key::T,
+        Key::U,
+        Key::V,
+        Key::W,
+        Key::X,
+        Key::Y,
+        Key::Z,
+    ];
+    LETTERS
+        .iter()
+        .position(|k| k == key)
+        .map(|i| (b'A' + i as u8) as char)
+}
+
+fn digit_for_key(key: &Key) -> Option<char> {
+    match key {
+        Key::KeyZero | Key::Kp0 => Some('0'),
+        Key::KeyOne | Key::Kp1 => Some('1'),
+        Key::KeyTwo | Key::Kp2 => Some('2'),
+        Key::KeyThree | Key::Kp3 => Some('3'),
+        Key::KeyFour | Key::Kp4 => Some('4'),
+        Key::KeyFive | Key::Kp5 => Some('5'),
+        Key::KeySix | Key::Kp6 => Some('6'),
+        Key::KeySeven | Key::Kp7 => Some('7'),
+        Key::KeyEight | Key::Kp8 => Some('8'),
+        Key::KeyNine | Key::Kp9 => Some('9'),
+        _ => None,
     }
 }
 
-fn process_new_game_input(state: &mut GameState, key: &KeyboardInfo, screen: &Screen) {
-    if key.pressed.contains(&Key::Space) {
-        *state = GameState::new(screen);
+fn process_shoot(state: &mut GameState, key: &KeyboardInfo, gamepad: &GamepadInfo) {
+    let fire_rate = if state.active_powerups.is_active(PowerUpKind::RapidFire) {
+        state.tuning.rapid_fire_rate
+    } else {
+        state.tuning.fire_rate
+    };
+    if state.settings.bindings.pressed(Action::Fire, key, gamepad)
+        && state.player.can_shoot(state.time, fire_rate)
+    {
+        state.player.last_fired = state.time;
+
+        let origin = state.player.shape.position;
+        let directions = if state.active_powerups.is_active(PowerUpKind::SpreadShot) {
+            FirePattern::Spread { count: 3, arc: 0.5 }.directions(&origin, &(origin + Vec2::UP))
+        } else {
+            vec![Vec2::UP]
+        };
+
+        let piercing = state.active_powerups.is_active(PowerUpKind::Piercing);
+        for direction in directions {
+            let mut bullet = Bullet {
+                piercing,
+                ..Bullet::new(&state.player, direction)
+            };
+            if piercing {
+                bullet.shape.kind = ShapeKind::Capsule {
+                    length: PIERCING_LENGTH,
+                };
+            }
+            state.bullets.push(bullet);
+        }
     }
 }
 
-fn process_shoot(state: &mut GameState, key: &KeyboardInfo) {
-    if key.pressed.contains(&Key::Space) {
-        if state.player.can_shoot(state.update_frame) {
-            state.player.last_fired = state.update_frame;
-            state.bullets.push(Bullet::new(&state.player));
+fn process_enemy_fire(state: &mut GameState) {
+    let target = state.player.shape.position;
+    for enemy in &mut state.enemies {
+        if enemy.can_enemy_shoot(state.time, state.tuning.enemy_fire_rate) {
+            enemy.last_fired = state.time;
+            if let Some(pattern) = &enemy.fire_pattern {
+                for direction in pattern.directions(&enemy.shape.position, &target) {
+                    state
+                        .enemy_bullets
+                        .push(Bullet::new_enemy(&enemy.shape, direction));
+                }
+            }
         }
     }
 }
 
-fn process_movement(state: &mut GameState, key: &KeyboardInfo, screen: &Screen, frame_time: f32) {
+fn process_movement(
+    state: &mut GameState,
+    key: &KeyboardInfo,
+    gamepad: &GamepadInfo,
+    screen: &Screen,
+    frame_time: f32,
+) {
     let player = &mut state.player;
 
-    if key.down.contains(&Key::Up) {
-        player.shape.position.y -= MOVEMENT_SPEED * frame_time;
+    let bindings = &state.settings.bindings;
+    let mut direction = Vec2::ZERO;
+    if bindings.down(Action::MoveUp, key, gamepad) {
+        direction.y -= 1.0;
     }
-    if key.down.contains(&Key::Down) {
-        player.shape.position.y += MOVEMENT_SPEED * frame_time;
+    if bindings.down(Action::MoveDown, key, gamepad) {
+        direction.y += 1.0;
     }
-    if key.down.contains(&Key::Left) {
-        player.shape.position.x -= MOVEMENT_SPEED * frame_time;
-        state.starfield_direction_modifier -= 0.05 * frame_time;
+    if bindings.down(Action::MoveLeft, key, gamepad) {
+        direction.x -= 1.0;
     }
-    if key.down.contains(&Key::Right) {
-        player.shape.position.x += MOVEMENT_SPEED * frame_time;
-        state.starfield_direction_modifier += 0.05 * frame_time;
+    if bindings.down(Action::MoveRight, key, gamepad) {
+        direction.x += 1.0;
     }
+    // A pushed stick takes over from the keys and d-pad, moving only as fast as it's pushed
+    let stick = gamepad.movement();
+    if stick != Vec2::ZERO {
+        direction = stick;
+    }
+    state.starfield_direction_modifier += direction.x * 0.05 * frame_time;
 
+    player.shape.velocity = direction * state.tuning.movement_speed;
+    player.shape.advance(frame_time);
     player.shape.clamp_to_screen(screen);
 }
 
 fn run_physics(state: &mut GameState, screen: &Screen, frame_time: f32) {
+    state.particles.update(frame_time);
+    state.popups.retain_mut(|popup| popup.update(frame_time));
+
+    let target = state.player.shape.position;
     for enemy in &mut state.enemies {
-        enemy.shape.position.y += enemy.shape.speed * frame_time;
+        enemy.movement.update(
+            &mut enemy.path,
+            &mut enemy.shape,
+            &target,
+            screen,
+            frame_time,
+        );
     }
-    for bullet in &mut state.bullets {
-        bullet.shape.position.y += -bullet.shape.speed * frame_time;
+    for bullet in state
+        .bullets
+        .iter_mut()
+        .chain(state.enemy_bullets.iter_mut())
+    {
+        bullet.shape.advance(frame_time);
     }
 
-    for enemy in &mut state.enemies {
-        for bullet in &mut state.bullets {
-            if bullet.shape.collides_with(&enemy.shape) {
-                bullet.collided = true;
-                enemy.is_dead = true;
-                state.score.add(enemy.shape.size.round() as u64);
+    for power_up in &mut state.powerups {
+        power_up.shape.advance(frame_time);
+    }
+
+    let mut fragments = vec![];
+    let grid = SpatialGrid::build(
+        BROADPHASE_CELL_SIZE,
+        state.enemies.iter().map(|enemy| enemy.shape.bounds()),
+    );
+    let mut candidates = vec![];
+    let mut hits = vec![];
+    for bullet in &mut state.bullets {
+        grid.query(&bullet.shape.swept_bounds(), &mut candidates);
+        // Swept so a fast bullet can't skip past an enemy, hitting them in the order it reaches them
+        hits.clear();
+        hits.extend(candidates.iter().filter_map(|&index| {
+            let enemy = &state.enemies[index];
+            if enemy.is_dead {
+                return None;
+            }
+            bullet.shape.sweep(&enemy.shape).map(|time| (time, index))
+        }));
+        hits.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        for &(time, index) in &hits {
+            if bullet.collided {
+                break;
+            }
+            let enemy = &mut state.enemies[index];
+            enemy.health = enemy.health.saturating_sub(1);
+            // Piercing rounds only carry on through what they destroy
+            bullet.collided = !bullet.piercing || enemy.health > 0;
+            let impact = bullet.shape.position_at(time);
+            state.particles.burst(&IMPACT, &impact);
+            if enemy.health > 0 {
+                continue;
+            }
+
+            enemy.is_dead = true;
+            state.stats.enemies_killed += 1;
+            let points = enemy.kind.score(enemy.shape.size);
+            let multiplier = state.combo.register_kill();
+            state.score.add(points * multiplier as u64);
+            state
+                .popups
+                .push(ScorePopup::new(enemy.shape.position, points, multiplier));
+            state.particles.burst_with_color(
+                &EXPLOSION,
+                &enemy.shape.position,
+                enemy.shape.color.clone(),
+            );
+            // Only the enemies that took a few hits go out with a big enough bang to shake
+            if enemy.kind.health() > 1 {
+                state.shake.add_trauma(TOUGH_KILL_TRAUMA);
+            }
+
+            if let Some((kind, count)) = enemy.kind.splits_into() {
+                fragments.extend(Ship::new_fragments(
+                    enemy,
+                    kind,
+                    count,
+                    state.time,
+                    &mut state.rng,
+                ));
+            }
+
+            if state.rng.gen_bool(POWERUP_DROP_CHANCE) {
+                let kind = *PowerUpKind::ALL.choose(&mut state.rng).unwrap();
+                state
+                    .powerups
+                    .push(PowerUp::new(enemy.shape.position, kind));
             }
         }
     }
+    state.enemies.extend(fragments);
+    // Before the bullets are cleared out, so none that hit the boss are drawn for another frame
+    state.check_boss_hits();
 
     state
         .enemies
         .retain(|enemy| is_on_screen(screen, &enemy.shape) && !enemy.is_dead);
     state
         .bullets
-        .retain(|bullet| is_on_screen(screen, &bullet.shape) && !bullet.collided);
+        .retain(|bullet| is_in_bounds(screen, &bullet.shape) && !bullet.collided);
+    state
+        .enemy_bullets
+        .retain(|bullet| is_in_bounds(screen, &bullet.shape));
 
-    state.check_player_hit();
+    state.collect_powerups();
+    state
+        .powerups
+        .retain(|power_up| is_on_screen(screen, &power_up.shape) && !power_up.collected);
+
+    state.check_player_hit(screen);
 }
 
+// Things enter from just above the screen, so only those well past the top have left.
+// Divers can head off either side, which is checked the same as for bullets.
 fn is_on_screen(screen: &Screen, shape: &Shape) -> bool {
-    shape.position.y < screen.height() + shape.size
+    shape.position.x > -shape.size
+        && shape.position.x < screen.width() + shape.size
+        && shape.position.y < screen.height() + shape.size
+        && shape.position.y > -shape.size * 2.0
 }
 
-fn draw(state: &mut GameState, screen: &Screen) {
+// Unlike enemies, which enter from above, bullets can leave through any edge
+fn is_in_bounds(screen: &Screen, shape: &Shape) -> bool {
+    shape.position.x > -shape.size
+        && shape.position.x < screen.width() + shape.size
+        && shape.position.y > -shape.size
+        && shape.position.y < screen.height() + shape.size
+}
+
+// `alpha` is how far between the last step and the next this frame is,
+// so moving things can be drawn between where they were and where they are
+fn draw(state: &mut GameState, camera: &mut Camera, alpha: f32) {
+    // Only the world shakes, the HUD and menus drawn after stay still
+    let center = Vec2::new(camera.width() / 2.0, camera.height() / 2.0);
+    let shake = state.shake.transform(state.time, center);
+    camera.with_transform(shake, |camera| {
+        draw_entities(state, camera, alpha);
+        if matches!(state.phase, GamePhase::Playing | GamePhase::Paused) {
+            draw_player(state, camera, alpha);
+        }
+    });
+
     match state.phase {
         GamePhase::MainMenu => {
-            draw_entities(state, screen);
-            draw_high_score(state, screen);
+            with_hud_scale(state, camera, draw_high_score);
 
-            screen.centered_text(
-                "Press Space to Start",
-                ((screen.width() / 2.0), (screen.height() / 2.0)),
+            camera.centered_text(
+                "Space Shooter",
+                ((camera.width() / 2.0), (camera.height() / 2.0) - 50.0),
                 TextSize::Title,
                 WHITE.into(),
             );
+            draw_menu(&mut state.widgets, camera, main_menu);
+
+            let seed = if state.seed_entry.is_empty() {
+                "Seed: Random (type digits to set)".to_string()
+            } else {
+                format!("Seed: {}_", state.seed_entry)
+            };
+            camera.centered_text(
+                &seed,
+                ((camera.width() / 2.0), (camera.height() / 2.0) + 130.0),
+                TextSize::Standard,
+                WHITE.into(),
+            );
+            draw_score_error(state, camera);
+        }
+        GamePhase::Options => {
+            camera.centered_text(
+                "Options",
+                ((camera.width() / 2.0), 70.0),
+                TextSize::Title,
+                WHITE.into(),
+            );
+            let mut ui = Ui::draw(&mut state.widgets, camera);
+            options_menu(&mut ui, &mut state.settings, camera.width());
+            drop(ui);
+
+            if let Some(error) = state.settings.error() {
+                camera.centered_text(
+                    error,
+                    ((camera.width() / 2.0), camera.height() - 15.0),
+                    TextSize::Standard,
+                    RED.into(),
+                );
+            }
+        }
+        GamePhase::Bindings => {
+            camera.centered_text(
+                "Key Bindings",
+                ((camera.width() / 2.0), 70.0),
+                TextSize::Title,
+                WHITE.into(),
+            );
+            let mut ui = Ui::draw(&mut state.widgets, camera);
+            bindings_menu(
+                &mut ui,
+                &state.settings.bindings,
+                state.rebinding,
+                camera.width(),
+            );
+            drop(ui);
+
+            let hint = if state.rebinding.is_some() {
+                "Press the key to use, or click to cancel"
+            } else {
+                "Choose an action to change its key"
+            };
+            camera.centered_text(
+                hint,
+                ((camera.width() / 2.0), camera.height() - 40.0),
+                TextSize::Standard,
+                WHITE.into(),
+            );
+        }
+        GamePhase::Leaderboard => {
+            draw_leaderboard(state, camera);
+
+            let back = state.settings.bindings.label(Action::Back);
+            camera.centered_text(
+                &format!("Press {back} to Return"),
+                ((camera.width() / 2.0), camera.height() - 40.0),
+                TextSize::Standard,
+                WHITE.into(),
+            );
+            draw_score_error(state, camera);
         }
         GamePhase::Playing => {
-            draw_entities(state, screen);
-            draw_player(state, screen);
-            draw_score(state, screen);
-            draw_high_score(state, screen);
+            with_hud_scale(state, camera, |state, camera| {
+                draw_hud(state, camera);
+                draw_boss_health(state, camera);
+                draw_wave_banner(state, camera);
+            });
         }
         GamePhase::Paused => {
-            draw_entities(state, screen);
-            draw_player(state, screen);
-            draw_score(state, screen);
-            draw_high_score(state, screen);
-
-            screen.centered_text(
-                "Paused (Press Space)",
-                ((screen.width() / 2.0), (screen.height() / 2.0)),
+            with_hud_scale(state, camera, draw_hud);
+
+            camera.centered_text(
+                "Paused",
+                ((camera.width() / 2.0), (camera.height() / 2.0) - 50.0),
                 TextSize::Title,
                 WHITE.into(),
             );
+            draw_menu(&mut state.widgets, camera, pause_menu);
+        }
+        GamePhase::EnterInitials => {
+            with_hud_scale(state, camera, |state, camera| {
+                draw_score(state, camera);
+                draw_high_score(state, camera);
+            });
+
+            camera.centered_text(
+                "New High Score!",
+                ((camera.width() / 2.0), (camera.height() / 2.0)),
+                TextSize::Title,
+                YELLOW.into(),
+            );
+            camera.centered_text(
+                &format!("Enter Your Initials: {}_", state.initials_entry),
+                ((camera.width() / 2.0), (camera.height() / 2.0) + 40.0),
+                TextSize::Standard,
+                WHITE.into(),
+            );
+            let confirm = state.settings.bindings.label(Action::Confirm);
+            camera.centered_text(
+                &format!("Press {confirm} to Confirm"),
+                ((camera.width() / 2.0), (camera.height() / 2.0) + 70.0),
+                TextSize::Standard,
+                WHITE.into(),
+            );
         }
         GamePhase::GameOver => {
-            draw_entities(state, screen);
-            draw_score(state, screen);
-            draw_high_score(state, screen);
+            with_hud_scale(state, camera, |state, camera| {
+                draw_score(state, camera);
+                draw_high_score(state, camera);
+            });
+
+            if let Some(rank) = state.placed_rank {
+                camera.centered_text(
+                    &format!("You Placed #{}", rank + 1),
+                    ((camera.width() / 2.0), (camera.height() / 2.0) - 50.0),
+                    TextSize::Standard,
+                    YELLOW.into(),
+                );
+            }
 
-            screen.centered_text(
-                "Game Over (Press Space)",
-                ((screen.width() / 2.0), (screen.height() / 2.0)),
+            camera.centered_text(
+                "Game Over",
+                ((camera.width() / 2.0), (camera.height() / 2.0)),
                 TextSize::Title,
                 RED.into(),
             );
+            camera.centered_text(
+                &format!("Seed: {}", state.seed),
+                ((camera.width() / 2.0), (camera.height() / 2.0) + 40.0),
+                TextSize::Standard,
+                WHITE.into(),
+            );
+            draw_menu(&mut state.widgets, camera, game_over_menu);
+            draw_score_error(state, camera);
         }
     }
 }
 
-fn draw_entities(state: &mut GameState, screen: &Screen) {
+// The HUD is drawn at the player's chosen scale. Menus aren't, so they stay where `step` checks for clicks.
+fn with_hud_scale(
+    state: &mut GameState,
+    camera: &mut Camera,
+    draw: impl FnOnce(&mut GameState, &mut Camera),
+) {
+    let scale = Transform::scale(state.settings.hud_scale);
+    camera.with_transform(scale, |camera| draw(state, camera));
+}
+
+fn draw_hud(state: &mut GameState, camera: &mut Camera) {
+    draw_score(state, camera);
+    draw_lives(state, camera);
+    draw_combo(state, camera);
+    draw_powerups(state, camera);
+    draw_high_score(state, camera);
+}
+
+fn draw_entities(state: &mut GameState, camera: &mut Camera, alpha: f32) {
     for enemy in &state.enemies {
-        screen.draw_rectangle(
-            enemy.shape.upper_left().into(),
-            Size {
-                width: enemy.shape.size,
-                height: enemy.shape.size,
-            },
-            enemy.shape.color.clone().into(),
-        );
+        draw_shape(camera, &enemy.shape, enemy.shape.color.clone(), alpha);
     }
 
     for bullet in &state.bullets {
-        screen.draw_circle(
-            bullet.shape.position.clone().into(),
-            bullet.shape.size / 2.0,
-            RED.into(),
+        draw_shape(camera, &bullet.shape, RED, alpha);
+    }
+
+    if let Some(boss) = &state.boss {
+        let color = if boss.hit_flash > 0.0 {
+            WHITE
+        } else {
+            boss.shape.color.clone()
+        };
+        draw_shape(camera, &boss.shape, color, alpha);
+    }
+
+    for power_up in &state.powerups {
+        draw_shape(camera, &power_up.shape, power_up.shape.color.clone(), alpha);
+    }
+
+    for bullet in &state.enemy_bullets {
+        draw_shape(camera, &bullet.shape, bullet.shape.color.clone(), alpha);
+    }
+
+    state.particles.draw(camera);
+
+    for popup in &state.popups {
+        camera.centered_text(
+            &popup.text,
+            (popup.position.x, popup.position.y),
+            TextSize::Standard,
+            popup.color.clone().into(),
         );
     }
 }
 
-fn draw_player(state: &mut GameState, screen: &Screen) {
-    screen.draw_circle(
-        (state.player.shape.position.x, state.player.shape.position.y).into(),
+// Draws exactly what the shape collides as
+fn draw_shape(camera: &Camera, shape: &Shape, color: Color, alpha: f32) {
+    match shape.collider_at(shape.position_at(alpha)) {
+        Collider::Rect(rect) => camera.draw_rectangle(
+            rect.point().into(),
+            Size {
+                width: rect.w,
+                height: rect.h,
+            },
+            color.into(),
+        ),
+        Collider::Circle(circle) => {
+            camera.draw_circle(circle.point().into(), circle.r, color.into())
+        }
+        Collider::Polygon(polygon) => {
+            // A fan of triangles out from the first point, which works for any convex polygon
+            if let Some((first, rest)) = polygon.points.split_first() {
+                for pair in rest.windows(2) {
+                    camera.draw_triangle(
+                        (*first).into(),
+                        pair[0].into(),
+                        pair[1].into(),
+                        color.clone().into(),
+                    );
+                }
+            }
+        }
+        Collider::Capsule(capsule) => {
+            camera.draw_line(
+                capsule.start.into(),
+                capsule.end.into(),
+                capsule.r * 2.0,
+                color.clone().into(),
+            );
+            camera.draw_circle(capsule.start.into(), capsule.r, color.clone().into());
+            camera.draw_circle(capsule.end.into(), capsule.r, color.into());
+        }
+    }
+}
+
+fn draw_player(state: &mut GameState, camera: &mut Camera, alpha: f32) {
+    let player = &state.player;
+    if player.is_respawning() {
+        return;
+    }
+    // Blink while invulnerable so the player knows they can't be hit
+    if player.invulnerable_time > 0.0 && (player.invulnerable_time * 5.0).fract() < 0.5 {
+        return;
+    }
+
+    let position = player.shape.position_at(alpha);
+    if state.active_powerups.is_active(PowerUpKind::Shield) {
+        let mut shield = AQUA;
+        shield.a = 0.4;
+        camera.draw_circle(
+            position.into(),
+            state.player.shape.size * 0.75,
+            shield.into(),
+        );
+    }
+
+    camera.draw_circle(
+        position.into(),
         state.player.shape.size / 2.0,
         YELLOW.into(),
     );
 }
 
-fn draw_score(state: &mut GameState, screen: &Screen) {
-    screen.standard_text(
+fn draw_score(state: &mut GameState, camera: &mut Camera) {
+    camera.standard_text(
         &format!("Score: {}", state.score.current_score()),
         (10.0, 30.0),
     );
 }
 
-fn draw_high_score(state: &mut GameState, screen: &Screen) {
-    screen.centered_text(
+fn draw_lives(state: &mut GameState, camera: &mut Camera) {
+    for i in 0..state.lives {
+        camera.draw_circle((18.0 + i as f32 * 20.0, 48.0).into(), 7.0, YELLOW.into());
+    }
+}
+
+fn draw_combo(state: &mut GameState, camera: &mut Camera) {
+    if !state.combo.is_active() {
+        return;
+    }
+
+    let (left, top, width, height) = (10.0, 62.0, 80.0, 6.0);
+    camera.text(
+        &format!("x{}", state.combo.multiplier()),
+        (left + width + 8.0, top + height + 4.0),
+        TextSize::Standard,
+        YELLOW.into(),
+    );
+
+    camera.draw_rectangle((left, top).into(), (width, height).into(), PANEL.into());
+    camera.draw_rectangle(
+        (left, top).into(),
+        (width * state.combo.remaining_fraction(), height).into(),
+        YELLOW.into(),
+    );
+}
+
+fn draw_powerups(state: &mut GameState, camera: &mut Camera) {
+    for (i, power_up) in state.active_powerups.iter().enumerate() {
+        camera.text(
+            &format!("{} {:.1}s", power_up.kind.label(), power_up.remaining),
+            (10.0, camera.height() - 15.0 - i as f32 * 22.0),
+            TextSize::Standard,
+            power_up.kind.color().into(),
+        );
+    }
+}
+
+fn draw_boss_health(state: &mut GameState, camera: &mut Camera) {
+    let Some(boss) = &state.boss else {
+        return;
+    };
+
+    let width = camera.width() * 0.5;
+    let left = (camera.width() - width) / 2.0;
+    let top = 40.0;
+    let height = 12.0;
+
+    camera.centered_text(
+        &boss.name(),
+        (camera.width() / 2.0, 30.0),
+        TextSize::Standard,
+        WHITE.into(),
+    );
+
+    camera.draw_rectangle((left, top).into(), (width, height).into(), PANEL.into());
+    camera.draw_rectangle(
+        (left, top).into(),
+        (width * boss.health_fraction(), height).into(),
+        boss.shape.color.clone().into(),
+    );
+
+    // Mark where each later phase begins
+    for threshold in Boss::phase_thresholds() {
+        let x = left + width * threshold;
+        camera.draw_line((x, top).into(), (x, top + height).into(), 2.0, WHITE.into());
+    }
+
+    if boss.is_entering() {
+        camera.centered_text(
+            "WARNING",
+            ((camera.width() / 2.0), (camera.height() / 3.0)),
+            TextSize::Title,
+            RED.into(),
+        );
+    }
+}
+
+fn draw_high_score(state: &mut GameState, camera: &mut Camera) {
+    camera.centered_text(
         &format!("High Score: {}", state.score.high_score()),
-        (screen.width() - 77.0, 30.0),
+        (camera.width() - 77.0, 30.0),
         TextSize::Standard,
         WHITE.into(),
     );
 }
+
+fn draw_leaderboard(state: &mut GameState, camera: &mut Camera) {
+    camera.centered_text(
+        "High Scores",
+        ((camera.width() / 2.0), 80.0),
+        TextSize::Title,
+        WHITE.into(),
+    );
+
+    let entries = state.score.entries();
+    if entries.is_empty() {
+        camera.centered_text(
+            "No Scores Yet",
+            ((camera.width() / 2.0), 140.0),
+            TextSize::Standard,
+            WHITE.into(),
+        );
+        return;
+    }
+
+    let left = camera.width() / 2.0 - 200.0;
+    for (i, entry) in entries.iter().enumerate() {
+        let y = 130.0 + i as f32 * 30.0;
+        let columns = [
+            (0.0, format!("{}.", i + 1)),
+            (50.0, entry.initials.clone()),
+            (110.0, entry.score.to_string()),
+            (200.0, format!("Wave {}", entry.wave)),
+            (290.0, entry.date_string()),
+        ];
+        for (x, text) in columns {
+            camera.text(&text, (left + x, y), TextSize::Standard, WHITE.into());
+        }
+    }
+}
+
+fn draw_score_error(state: &mut GameState, camera: &mut Camera) {
+    if let Some(error) = state.score.error() {
+        camera.centered_text(
+            error,
+            ((camera.width() / 2.0), camera.height() - 15.0),
+            TextSize::Standard,
+            RED.into(),
+        );
+    }
+}
+
+fn draw_wave_banner(state: &mut GameState, camera: &mut Camera) {
+    if let Some(banner) = state.waves.banner() {
+        camera.centered_text(
+            &banner.title,
+            ((camera.width() / 2.0), (camera.height() / 3.0)),
+            TextSize::Title,
+            WHITE.into(),
+        );
+        if let Some(subtitle) = &banner.subtitle {
+            camera.centered_text(
+                subtitle,
+                ((camera.width() / 2.0), (camera.height() / 3.0) + 30.0),
+                TextSize::Standard,
+                WHITE.into(),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        enemies::EnemyKind, infrastructure::RecordingScreen, math::Position, movement::Movement,
+        state::EnemySpec,
+    };
+
+    const SEED: u64 = 42;
+
+    fn render(screen: &RecordingScreen, state: GameState) -> String {
+        let game = Game::in_memory(screen);
+        *game.state.lock().unwrap() = state;
+        game.render_frame(screen);
+        screen.transcript()
+    }
+
+    // Scores and settings are kept in memory so local files can't change the output
+    fn new_state(screen: &RecordingScreen) -> GameState {
+        GameState::new_with_seed(screen, SEED, HighScore::in_memory(), Settings::in_memory())
+    }
+
+    fn playing_state(screen: &RecordingScreen) -> GameState {
+        let mut state = new_state(screen);
+        state.phase = GamePhase::Playing;
+
+        let spec = EnemySpec {
+            x: Some(0.25),
+            size: Some(32.0),
+            speed: Some(100.0),
+            ..Default::default()
+        };
+        let enemy = Ship::new_enemy(screen, 0.0, &spec, &mut state.rng);
+        state.enemies.push(enemy);
+        let mine = EnemySpec {
+            x: Some(0.75),
+            kind: EnemyKind::Mine,
+            ..spec
+        };
+        let mine = Ship::new_enemy(screen, 0.0, &mine, &mut state.rng);
+        state.enemies.push(mine);
+        state.bullets.push(Bullet::new(&state.player, Vec2::UP));
+        state.powerups.push(PowerUp::new(
+            Position { x: 600.0, y: 100.0 },
+            PowerUpKind::Shield,
+        ));
+        state.active_powerups.activate(PowerUpKind::RapidFire);
+        state.boss = Some(Boss::new(screen, 1));
+        for _ in 0..3 {
+            state.combo.register_kill();
+        }
+        state.popups.push(ScorePopup::new(
+            Position { x: 200.0, y: 150.0 },
+            32,
+            state.combo.multiplier(),
+        ));
+        state
+    }
+
+    #[test]
+    fn main_menu_snapshot() {
+        let screen = RecordingScreen::new(800.0, 600.0);
+        let mut state = new_state(&screen);
+        state.seed_entry = "123".to_string();
+        insta::assert_snapshot!(render(&screen, state));
+    }
+
+    #[test]
+    fn options_snapshot() {
+        let screen = RecordingScreen::new(800.0, 600.0);
+        let mut state = new_state(&screen);
+        state.phase = GamePhase::Options;
+        state.settings.difficulty = Difficulty::Hard;
+        state.settings.hud_scale = 1.25;
+        insta::assert_snapshot!(render(&screen, state));
+    }
+
+    #[test]
+    fn bindings_snapshot() {
+        let screen = RecordingScreen::new(800.0, 600.0);
+        let mut state = new_state(&screen);
+        state.phase = GamePhase::Bindings;
+        state.settings.bindings = ControlScheme::Wasd.bindings();
+        state.rebinding = Some(Action::Fire);
+        insta::assert_snapshot!(render(&screen, state));
+    }
+
+    #[test]
+    fn rebinding_takes_next_key() {
+        let screen = RecordingScreen::new(800.0, 600.0);
+        let mut state = new_state(&screen);
+        state.phase = GamePhase::Bindings;
+        state.rebinding = Some(Action::Fire);
+
+        let (mouse, mut key, gamepad) = PendingInput::default().take();
+        key.pressed.push(Key::Z);
+        step(&mut state, mouse, key, gamepad, &screen, 1.0 / 60.0);
+        assert_eq!(None, state.rebinding);
+        assert_eq!([Key::Z], state.settings.bindings.keys(Action::Fire));
+    }
+
+    #[test]
+    fn new_games_keep_unsaved_settings() {
+        let screen = RecordingScreen::new(800.0, 600.0);
+        let mut state = new_state(&screen);
+        state.settings.difficulty = Difficulty::Hard;
+        state.settings.starfield = false;
+
+        start_game(&mut state, &screen, Some(SEED));
+        assert_eq!(Difficulty::Hard, state.settings.difficulty);
+        assert!(!state.settings.starfield);
+        assert_eq!(
+            Difficulty::Hard.tuning().spawn_rate,
+            state.tuning.spawn_rate
+        );
+    }
+
+    #[test]
+    fn diving_enemy_leaving_sideways_clears_wave() {
+        let screen = RecordingScreen::new(800.0, 600.0);
+        let mut state = new_state(&screen);
+        state.phase = GamePhase::Playing;
+        // Everything in the wave has spawned, only the diver is left
+        state.waves.spawned = vec![u32::MAX; state.waves.spawned.len()];
+        let wave = state.waves.wave_number;
+
+        let spec = EnemySpec {
+            x: Some(0.9),
+            speed: Some(200.0),
+            movement: Movement::Dive {
+                delay: 0.0,
+                speed_multiplier: 2.0,
+            },
+            ..Default::default()
+        };
+        let mut enemy = Ship::new_enemy(&screen, 0.0, &spec, &mut state.rng);
+        // Locked on level with the player, so it never moves down the screen
+        enemy.shape.position = Position { x: 700.0, y: 300.0 };
+        enemy.path.heading = Some(Vec2::new(1.0, 0.0));
+        enemy.shape.velocity = Vec2::new(400.0, 0.0);
+        state.enemies.push(enemy);
+
+        for _ in 0..90 {
+            let (mouse, key, gamepad) = PendingInput::default().take();
+            step(&mut state, mouse, key, gamepad, &screen, 1.0 / 60.0);
+        }
+        // The next wave may already be spawning, but the diver is gone
+        assert_eq!(wave + 1, state.waves.wave_number);
+        assert!(state.enemies.iter().all(|e| e.path.heading.is_none()));
+    }
+
+    #[test]
+    fn bullets_hitting_boss_are_removed_the_same_step() {
+        let screen = RecordingScreen::new(800.0, 600.0);
+        let mut state = new_state(&screen);
+        state.phase = GamePhase::Playing;
+        let mut boss = Boss::new(&screen, 1);
+        boss.shape.position = Position { x: 400.0, y: 200.0 };
+        boss.shape.previous_position = boss.shape.position;
+        let mut bullet = Bullet::new(&state.player, Vec2::UP);
+        bullet.shape.position = boss.shape.position;
+        bullet.shape.previous_position = boss.shape.position;
+        state.bullets.push(bullet);
+        state.boss = Some(boss);
+
+        run_physics(&mut state, &screen, 1.0 / 60.0);
+        assert!(state.bullets.is_empty());
+        let boss = state.boss.as_ref().unwrap();
+        assert_eq!(boss.max_health - 1, boss.health);
+    }
+
+    #[test]
+    fn analog_stick_moves_partway() {
+        let screen = RecordingScreen::new(800.0, 600.0);
+        let mut state = playing_state(&screen);
+        let start = state.player.shape.position;
+
+        let (mouse, mut key, mut gamepad) = PendingInput::default().take();
+        gamepad.connected = true;
+        gamepad.left_stick = Vec2::new(0.6, 0.0).into();
+        key.down.push(Key::Left);
+        step(&mut state, mouse, key, gamepad, &screen, 0.1);
+
+        // Past the deadzone the stick is at half throw, and overrides the held key
+        let moved = state.player.shape.position - start;
+        assert_eq!(
+            Vec2::new(state.tuning.movement_speed * 0.5 * 0.1, 0.0),
+            moved
+        );
+    }
+
+    #[test]
+    fn hud_scale_snapshot() {
+        let screen = RecordingScreen::new(800.0, 600.0);
+        let mut state = playing_state(&screen);
+        state.settings.hud_scale = 1.5;
+        state.settings.starfield = false;
+        insta::assert_snapshot!(render(&screen, state));
+    }
+
+    #[test]
+    fn leaderboard_snapshot() {
+        let screen = RecordingScreen::new(800.0, 600.0);
+        let mut state = new_state(&screen);
+        state.phase = GamePhase::Leaderboard;
+        // The score carries over, so each entry beats the one before
+        for (initials, points) in [("CAT", 50), ("AAA", 450), ("BOB", 700)] {
+            state.score.add(points);
+            state.score.record(initials, 3, 1_700_000_000);
+        }
+        insta::assert_snapshot!(render(&screen, state));
+    }
+
+    #[test]
+    fn enter_initials_snapshot() {
+        let screen = RecordingScreen::new(800.0, 600.0);
+        let mut state = playing_state(&screen);
+        state.phase = GamePhase::EnterInitials;
+        state.score.add(300);
+        state.initials_entry = "AB".to_string();
+        insta::assert_snapshot!(render(&screen, state));
+    }
+
+    #[test]
+    fn playing_snapshot() {
+        let screen = RecordingScreen::new(800.0, 600.0);
+        let state = playing_state(&screen);
+        insta::assert_snapshot!(render(&screen, state));
+    }
+
+    #[test]
+    fn paused_snapshot() {
+        let screen = RecordingScreen::new(800.0, 600.0);
+        let mut state = playing_state(&screen);
+        state.phase = GamePhase::Paused;
+        insta::assert_snapshot!(render(&screen, state));
+    }
+
+    #[test]
+    fn game_over_snapshot() {
+        let screen = RecordingScreen::new(800.0, 600.0);
+        let mut state = playing_state(&screen);
+        state.phase = GamePhase::GameOver;
+        state.player.is_dead = true;
+        insta::assert_snapshot!(render(&screen, state));
+    }
+}
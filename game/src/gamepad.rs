@@ -0,0 +1,58 @@
+use crate::{exports::caffeinated_gorilla::space::game_api::GamepadInfo, math::Vec2};
+
+// Sticks rarely rest at exactly zero, anything inside this is treated as centered
+const STICK_DEADZONE: f32 = 0.2;
+
+impl Default for GamepadInfo {
+    /// No gamepad connected
+    fn default() -> Self {
+        GamepadInfo {
+            connected: false,
+            left_stick: Vec2::ZERO.into(),
+            right_stick: Vec2::ZERO.into(),
+            left_trigger: 0.0,
+            right_trigger: 0.0,
+            pressed: vec![],
+            released: vec![],
+            down: vec![],
+        }
+    }
+}
+
+impl GamepadInfo {
+    /// The left stick as a direction up to length 1. The deadzone is cut out and
+    /// the rest stretched to fill it, so pushing just past it still moves slowly.
+    pub fn movement(&self) -> Vec2 {
+        let stick: Vec2 = self.left_stick.into();
+        let length = stick.length().min(1.0);
+        if !self.connected || length <= STICK_DEADZONE {
+            return Vec2::ZERO;
+        }
+        stick.normalize() * ((length - STICK_DEADZONE) / (1.0 - STICK_DEADZONE))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stick_movement_skips_deadzone() {
+        let mut gamepad = GamepadInfo {
+            connected: true,
+            ..Default::default()
+        };
+        gamepad.left_stick = Vec2::new(0.1, 0.1).into();
+        assert_eq!(Vec2::ZERO, gamepad.movement());
+
+        gamepad.left_stick = Vec2::new(0.6, 0.0).into();
+        assert!(gamepad.movement().distance(Vec2::new(0.5, 0.0)) < 0.0001);
+
+        // Corners of a square stick range don't move faster than straight ahead
+        gamepad.left_stick = Vec2::new(1.0, -1.0).into();
+        assert!((gamepad.movement().length() - 1.0).abs() < 0.0001);
+
+        gamepad.connected = false;
+        assert_eq!(Vec2::ZERO, gamepad.movement());
+    }
+}
@@ -9,6 +9,7 @@ pub trait GameScreenInterface: Send + Sync {
     fn draw_image(&self, filename: &str, position: Position, size: Option<Size>);
     fn draw_circle(&self, position: Position, width: f32, color: GameColor);
     fn draw_rectangle(&self, position: Position, size: Size, color: GameColor);
+    fn draw_triangle(&self, first: Position, second: Position, third: Position, color: GameColor);
 
     fn width(&self) -> f32;
     fn height(&self) -> f32;
@@ -1,5 +1,5 @@
+use crate::exports::caffeinated_gorilla::space::game_api::{GamepadInfo, KeyboardInfo, MouseInfo};
 use crate::exports::caffeinated_gorilla::space::game_api::{Guest, GuestGameInstance};
-use crate::exports::caffeinated_gorilla::space::game_api::{KeyboardInfo, MouseInfo};
 
 pub use crate::caffeinated_gorilla::space::host_api::GameScreen;
 pub use crate::caffeinated_gorilla::space::host_api::Shader;
@@ -29,13 +29,18 @@ impl GuestGameInstance for Game {
         &self,
         mouse: MouseInfo,
         key: KeyboardInfo,
+        gamepad: GamepadInfo,
         screen: &GameScreen,
         frame_time: f32,
     ) {
-        Game::update_frame(self, mouse, key, screen, frame_time);
+        Game::update_frame(self, mouse, key, gamepad, screen, frame_time);
     }
 
     fn render_frame(&self, screen: &GameScreen) {
         Game::render_frame(self, screen);
     }
+
+    fn set_simulation_rate(&self, rate: f32) {
+        Game::set_simulation_rate(self, rate);
+    }
 }
@@ -16,3 +16,8 @@ pub type Shader = GameShader;
 
 #[cfg(not(feature = "hotreload"))]
 pub use direct::{GameScreenInterface, ShaderInterface, TextDimensions};
+
+#[cfg(not(feature = "hotreload"))]
+mod recording;
+#[cfg(not(feature = "hotreload"))]
+pub use recording::{DrawCall, RecordingScreen};
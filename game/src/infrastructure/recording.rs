@@ -0,0 +1,286 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use crate::caffeinated_gorilla::space::types::{GameColor, Position, Size};
+
+use super::direct::{GameScreenInterface, GameShader, ShaderInterface, TextDimensions};
+
+/// A single call made against a `RecordingScreen`
+#[derive(Debug, Clone)]
+pub enum DrawCall {
+    Text {
+        text: String,
+        position: Position,
+        size: u16,
+        color: GameColor,
+    },
+    Line {
+        first: Position,
+        second: Position,
+        thickness: f32,
+        color: GameColor,
+    },
+    Image {
+        filename: String,
+        position: Position,
+        size: Option<Size>,
+    },
+    Circle {
+        position: Position,
+        width: f32,
+        color: GameColor,
+    },
+    Rectangle {
+        position: Position,
+        size: Size,
+        color: GameColor,
+    },
+    Triangle {
+        first: Position,
+        second: Position,
+        third: Position,
+        color: GameColor,
+    },
+    Shader {
+        direction_modifier: f32,
+    },
+}
+
+struct Point<'a>(&'a Position);
+
+impl fmt::Display for Point<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({:.1}, {:.1})", self.0.x, self.0.y)
+    }
+}
+
+struct Rgba<'a>(&'a GameColor);
+
+impl fmt::Display for Rgba<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "rgba({:.2}, {:.2}, {:.2}, {:.2})",
+            self.0.r, self.0.g, self.0.b, self.0.a
+        )
+    }
+}
+
+// One line per call, with floats rounded so snapshots don't churn on noise
+impl fmt::Display for DrawCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DrawCall::Text {
+                text,
+                position,
+                size,
+                color,
+            } => write!(
+                f,
+                "text {text:?} at {} size {size} {}",
+                Point(position),
+                Rgba(color)
+            ),
+            DrawCall::Line {
+                first,
+                second,
+                thickness,
+                color,
+            } => write!(
+                f,
+                "line {} to {} thickness {thickness:.1} {}",
+                Point(first),
+                Point(second),
+                Rgba(color)
+            ),
+            DrawCall::Image {
+                filename,
+                position,
+                size,
+            } => match size {
+                Some(size) => write!(
+                    f,
+                    "image {filename:?} at {} size {:.1}x{:.1}",
+                    Point(position),
+                    size.width,
+                    size.height
+                ),
+                None => write!(f, "image {filename:?} at {}", Point(position)),
+            },
+            DrawCall::Circle {
+                position,
+                width,
+                color,
+            } => write!(
+                f,
+                "circle at {} radius {width:.1} {}",
+                Point(position),
+                Rgba(color)
+            ),
+            DrawCall::Rectangle {
+                position,
+                size,
+                color,
+            } => write!(
+                f,
+                "rectangle at {} size {:.1}x{:.1} {}",
+                Point(position),
+                size.width,
+                size.height,
+                Rgba(color)
+            ),
+            DrawCall::Triangle {
+                first,
+                second,
+                third,
+                color,
+            } => write!(
+                f,
+                "triangle {} {} {} {}",
+                Point(first),
+                Point(second),
+                Point(third),
+                Rgba(color)
+            ),
+            DrawCall::Shader { direction_modifier } => {
+                write!(f, "shader direction {direction_modifier:.2}")
+            }
+        }
+    }
+}
+
+type CallLog = Arc<Mutex<Vec<DrawCall>>>;
+
+/// A screen that draws nothing, only remembering what it was asked to draw.
+///
+/// Text is measured as if every character were half as wide as it is tall,
+/// so layout is the same on every machine.
+pub struct RecordingScreen {
+    width: f32,
+    height: f32,
+    calls: CallLog,
+}
+
+impl RecordingScreen {
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            calls: CallLog::default(),
+        }
+    }
+
+    /// Every call since the screen was created or last cleared
+    pub fn calls(&self) -> Vec<DrawCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    pub fn clear(&self) {
+        self.calls.lock().unwrap().clear();
+    }
+
+    /// The recorded calls, one per line
+    pub fn transcript(&self) -> String {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|call| format!("{call}\n"))
+            .collect()
+    }
+
+    fn record(&self, call: DrawCall) {
+        self.calls.lock().unwrap().push(call);
+    }
+}
+
+impl GameScreenInterface for RecordingScreen {
+    fn draw_text(&self, text: &str, position: Position, size: u16, color: GameColor) {
+        self.record(DrawCall::Text {
+            text: text.to_string(),
+            position,
+            size,
+            color,
+        });
+    }
+
+    fn draw_line(&self, first: Position, second: Position, thickness: f32, color: GameColor) {
+        self.record(DrawCall::Line {
+            first,
+            second,
+            thickness,
+            color,
+        });
+    }
+
+    fn draw_image(&self, filename: &str, position: Position, size: Option<Size>) {
+        self.record(DrawCall::Image {
+            filename: filename.to_string(),
+            position,
+            size,
+        });
+    }
+
+    fn draw_circle(&self, position: Position, width: f32, color: GameColor) {
+        self.record(DrawCall::Circle {
+            position,
+            width,
+            color,
+        });
+    }
+
+    fn draw_rectangle(&self, position: Position, size: Size, color: GameColor) {
+        self.record(DrawCall::Rectangle {
+            position,
+            size,
+            color,
+        });
+    }
+
+    fn draw_triangle(&self, first: Position, second: Position, third: Position, color: GameColor) {
+        self.record(DrawCall::Triangle {
+            first,
+            second,
+            third,
+            color,
+        });
+    }
+
+    fn width(&self) -> f32 {
+        self.width
+    }
+
+    fn height(&self) -> f32 {
+        self.height
+    }
+
+    fn measure_text(&self, text: &str, size: u16) -> TextDimensions {
+        let size = size as f32;
+        TextDimensions {
+            width: text.chars().count() as f32 * size / 2.0,
+            height: size,
+            offset_y: size * 0.75,
+        }
+    }
+
+    fn load_shader(&self, _fragment: &str, _vertex: &str) -> GameShader {
+        Box::new(RecordingShader {
+            calls: self.calls.clone(),
+        })
+    }
+}
+
+struct RecordingShader {
+    calls: CallLog,
+}
+
+impl ShaderInterface for RecordingShader {
+    fn render(&self, direction_modifier: f32) {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(DrawCall::Shader { direction_modifier });
+    }
+}
@@ -1,6 +1,7 @@
 wit_bindgen::generate!({
     world: "space-shooter-game",
     path: "../wit",
+    additional_derives: [serde::Serialize, serde::Deserialize],
 });
 
 #[allow(dead_code)]
@@ -12,13 +13,28 @@ pub use game::Game;
 mod infrastructure;
 pub use infrastructure::*;
 
+mod bindings;
+mod boss;
+mod combo;
+mod enemies;
+mod gamepad;
 mod state;
 
 #[cfg(feature = "hotreload")]
 export!(GameGuest);
 
 #[allow(dead_code)]
-mod math;
+pub mod math;
 
+mod movement;
+mod particles;
+mod powerups;
 mod score;
+mod settings;
 mod shaders;
+mod stats;
+pub use stats::{DeathCause, RunSummary};
+mod timestep;
+mod tuning;
+pub use tuning::Tuning;
+mod waves;
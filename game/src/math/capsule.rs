@@ -0,0 +1,136 @@
+use super::{polygon::intervals_overlap, Circle, Polygon, Position, Rect, Vec2};
+
+/// A line segment grown by a radius, a rectangle with rounded ends
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Capsule {
+    pub start: Position,
+    pub end: Position,
+    pub r: f32,
+}
+
+impl Capsule {
+    pub const fn new(start: Position, end: Position, r: f32) -> Self {
+        Capsule { start, end, r }
+    }
+
+    /// Returns the smallest `Rect` containing the `Capsule`
+    pub fn bounds(&self) -> Rect {
+        let left = self.start.x.min(self.end.x) - self.r;
+        let top = self.start.y.min(self.end.y) - self.r;
+        let right = self.start.x.max(self.end.x) + self.r;
+        let bottom = self.start.y.max(self.end.y) + self.r;
+        Rect::new(left, top, right - left, bottom - top)
+    }
+
+    /// Translate `Capsule` by `offset` vector
+    pub fn offset(self, offset: Vec2) -> Capsule {
+        Capsule::new(self.start + offset, self.end + offset, self.r)
+    }
+
+    /// Returns the point on the centre line nearest to `point`
+    pub fn closest_point(&self, point: Position) -> Position {
+        closest_on_segment(self.start, self.end, point)
+    }
+
+    /// The interval covered by the `Capsule` along the unit vector `axis`
+    pub fn project(&self, axis: Vec2) -> (f32, f32) {
+        let (a, b) = (self.start.dot(axis), self.end.dot(axis));
+        (a.min(b) - self.r, a.max(b) + self.r)
+    }
+
+    /// Checks whether the `Capsule` overlaps a `Circle`
+    pub fn overlaps_circle(&self, circle: &Circle) -> bool {
+        let center = circle.point();
+        self.closest_point(center).distance(center) <= self.r + circle.r
+    }
+
+    /// Checks whether the `Capsule` overlaps another `Capsule`
+    pub fn overlaps(&self, other: &Capsule) -> bool {
+        segment_distance((self.start, self.end), (other.start, other.end)) <= self.r + other.r
+    }
+
+    /// Checks whether the `Capsule` overlaps a `Polygon`, with a separating-axis test
+    pub fn overlaps_polygon(&self, polygon: &Polygon) -> bool {
+        let segment_axis =
+            Vec2::new(self.start.y - self.end.y, self.end.x - self.start.x).normalize();
+        // Around the rounded ends, the axis from each corner to the nearest point on the line
+        let corner_axes = polygon
+            .points
+            .iter()
+            .map(|&point| (point - self.closest_point(point)).normalize());
+        polygon
+            .axes()
+            .chain(Some(segment_axis))
+            .chain(corner_axes)
+            .filter(|axis| *axis != Vec2::ZERO)
+            .all(|axis| intervals_overlap(self.project(axis), polygon.project(axis)))
+    }
+}
+
+fn closest_on_segment(start: Position, end: Position, point: Position) -> Position {
+    let direction = end - start;
+    let length_squared = direction.dot(direction);
+    if length_squared <= f32::EPSILON {
+        return start;
+    }
+    let t = ((point - start).dot(direction) / length_squared).clamp(0.0, 1.0);
+    start + direction * t
+}
+
+// Which side of the line through a and b that c is on
+fn orientation(a: Position, b: Position, c: Position) -> f32 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+fn segment_distance(a: (Position, Position), b: (Position, Position)) -> f32 {
+    let crosses = orientation(a.0, a.1, b.0) * orientation(a.0, a.1, b.1) < 0.0
+        && orientation(b.0, b.1, a.0) * orientation(b.0, b.1, a.1) < 0.0;
+    if crosses {
+        return 0.0;
+    }
+    [
+        closest_on_segment(a.0, a.1, b.0).distance(b.0),
+        closest_on_segment(a.0, a.1, b.1).distance(b.1),
+        closest_on_segment(b.0, b.1, a.0).distance(a.0),
+        closest_on_segment(b.0, b.1, a.1).distance(a.1),
+    ]
+    .into_iter()
+    .fold(f32::MAX, f32::min)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Capsule, Circle, Polygon, Position, Rect};
+
+    fn beam(x: f32) -> Capsule {
+        Capsule::new(Position { x, y: 0.0 }, Position { x, y: 30.0 }, 2.0)
+    }
+
+    #[test]
+    fn capsule_overlaps() {
+        assert!(beam(0.0).overlaps_circle(&Circle::new(4.0, 15.0, 2.5)));
+        assert!(!beam(0.0).overlaps_circle(&Circle::new(0.0, 34.0, 1.5)));
+
+        let crossing = Capsule::new(
+            Position { x: -10.0, y: 10.0 },
+            Position { x: 10.0, y: 10.0 },
+            0.5,
+        );
+        assert!(beam(0.0).overlaps(&crossing));
+        assert!(!beam(0.0).overlaps(&beam(4.5)));
+        assert!(beam(0.0).overlaps(&beam(4.0)));
+    }
+
+    #[test]
+    fn capsule_overlaps_polygon() {
+        let square: Polygon = Rect::new(3.0, 10.0, 5.0, 5.0).into();
+        assert!(!beam(0.0).overlaps_polygon(&square));
+        assert!(beam(1.5).overlaps_polygon(&square));
+
+        // Clear of the bottom end's rounded corner, though its bounds overlap
+        let corner: Polygon = Rect::new(1.8, 31.8, 5.0, 5.0).into();
+        assert!(!beam(0.0).overlaps_polygon(&corner));
+        let touching: Polygon = Rect::new(1.0, 31.0, 5.0, 5.0).into();
+        assert!(beam(0.0).overlaps_polygon(&touching));
+    }
+}
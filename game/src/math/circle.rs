@@ -69,4 +69,84 @@ impl Circle {
     pub fn offset(self, offset: Position) -> Circle {
         Circle::new(self.x + offset.x, self.y + offset.y, self.r)
     }
+
+    /// Returns how far along `motion`, from 0 to 1, a ray from `origin` first touches the `Circle`.
+    /// A ray starting inside hits at 0.
+    pub fn ray_intersection(&self, origin: &Position, motion: &Position) -> Option<f32> {
+        let (mx, my) = (origin.x - self.x, origin.y - self.y);
+        let c = mx * mx + my * my - self.r * self.r;
+        if c <= 0.0 {
+            return Some(0.0);
+        }
+        let a = motion.x * motion.x + motion.y * motion.y;
+        let b = mx * motion.x + my * motion.y;
+        let discriminant = b * b - a * c;
+        if b >= 0.0 || discriminant < 0.0 {
+            return None;
+        }
+        let t = (-b - discriminant.sqrt()) / a;
+        (t <= 1.0).then_some(t)
+    }
+
+    /// Returns when, from 0 to 1, the `Circle` first overlaps `rect` while moving by `motion`
+    pub fn sweep_rect(&self, motion: &Position, rect: &Rect) -> Option<f32> {
+        if self.overlaps_rect(rect) {
+            return Some(0.0);
+        }
+
+        // The centre hitting the rect grown by the radius, with rounded corners
+        let expanded = Rect::new(
+            rect.x - self.r,
+            rect.y - self.r,
+            rect.w + self.r * 2.0,
+            rect.h + self.r * 2.0,
+        );
+        let t = expanded.ray_intersection(&self.point(), motion)?;
+        let hit = Position {
+            x: self.x + motion.x * t,
+            y: self.y + motion.y * t,
+        };
+        let beside_x = hit.x < rect.left() || hit.x > rect.right();
+        let beside_y = hit.y < rect.top() || hit.y > rect.bottom();
+        if !(beside_x && beside_y) {
+            return Some(t);
+        }
+
+        // Entering by a corner only counts once within the radius of it
+        let corner = Circle::new(
+            if hit.x < rect.left() {
+                rect.left()
+            } else {
+                rect.right()
+            },
+            if hit.y < rect.top() {
+                rect.top()
+            } else {
+                rect.bottom()
+            },
+            self.r,
+        );
+        corner.ray_intersection(&self.point(), motion)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sweep_circle_time_of_impact() {
+        let bullet = Circle::new(10.0, 100.0, 5.0);
+        let enemy = Rect::new(0.0, 40.0, 20.0, 10.0);
+        let motion = Position { x: 0.0, y: -200.0 };
+        assert!(!bullet.offset(motion).overlaps_rect(&enemy));
+        assert_eq!(Some(0.225), bullet.sweep_rect(&motion, &enemy));
+
+        // Passes the corner's bounding box but not within the radius of it
+        let corner_miss = Circle::new(-20.0, 38.5, 5.0);
+        let diagonal = Position { x: 20.0, y: 20.0 };
+        assert_eq!(None, corner_miss.sweep_rect(&diagonal, &enemy));
+        let corner_hit = Circle::new(-20.0, 35.7, 5.0);
+        assert!(corner_hit.sweep_rect(&diagonal, &enemy).is_some());
+    }
 }
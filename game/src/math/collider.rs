@@ -0,0 +1,102 @@
+use super::{Capsule, Circle, Polygon, Rect};
+
+/// Any of the shapes that can be tested against each other
+#[derive(Clone, Debug, PartialEq)]
+pub enum Collider {
+    Rect(Rect),
+    Circle(Circle),
+    Polygon(Polygon),
+    Capsule(Capsule),
+}
+
+impl Collider {
+    /// Returns the smallest `Rect` containing the shape
+    pub fn bounds(&self) -> Rect {
+        match self {
+            Collider::Rect(rect) => *rect,
+            Collider::Circle(circle) => Rect::new(
+                circle.x - circle.r,
+                circle.y - circle.r,
+                circle.r * 2.0,
+                circle.r * 2.0,
+            ),
+            Collider::Polygon(polygon) => polygon.bounds(),
+            Collider::Capsule(capsule) => capsule.bounds(),
+        }
+    }
+
+    /// Checks whether the shapes overlap, whichever kinds they are
+    pub fn overlaps(&self, other: &Collider) -> bool {
+        use Collider::*;
+
+        match (self, other) {
+            (Rect(a), Rect(b)) => a.overlaps(b),
+            (Circle(a), Circle(b)) => a.overlaps(b),
+            (Polygon(a), Polygon(b)) => a.overlaps(b),
+            (Capsule(a), Capsule(b)) => a.overlaps(b),
+
+            (Circle(circle), Rect(rect)) | (Rect(rect), Circle(circle)) => {
+                circle.overlaps_rect(rect)
+            }
+            (Polygon(polygon), Rect(rect)) | (Rect(rect), Polygon(polygon)) => {
+                polygon.overlaps(&(*rect).into())
+            }
+            (Capsule(capsule), Rect(rect)) | (Rect(rect), Capsule(capsule)) => {
+                capsule.overlaps_polygon(&(*rect).into())
+            }
+            (Polygon(polygon), Circle(circle)) | (Circle(circle), Polygon(polygon)) => {
+                polygon.overlaps_circle(circle)
+            }
+            (Capsule(capsule), Circle(circle)) | (Circle(circle), Capsule(capsule)) => {
+                capsule.overlaps_circle(circle)
+            }
+            (Capsule(capsule), Polygon(polygon)) | (Polygon(polygon), Capsule(capsule)) => {
+                capsule.overlaps_polygon(polygon)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::math::Position;
+
+    // One of each kind, all about 10 across and centred on (x, 0)
+    fn colliders(x: f32) -> [Collider; 4] {
+        [
+            Collider::Rect(Rect::new(x - 5.0, -5.0, 10.0, 10.0)),
+            Collider::Circle(Circle::new(x, 0.0, 5.0)),
+            Collider::Polygon(Polygon::new(vec![
+                Position {
+                    x: x - 5.0,
+                    y: -5.0,
+                },
+                Position {
+                    x: x + 5.0,
+                    y: -5.0,
+                },
+                Position { x, y: 5.0 },
+            ])),
+            Collider::Capsule(Capsule::new(
+                Position { x: x - 3.0, y: 0.0 },
+                Position { x: x + 3.0, y: 0.0 },
+                2.0,
+            )),
+        ]
+    }
+
+    #[test]
+    fn every_pairing_overlaps_either_way_round() {
+        for a in colliders(0.0) {
+            for b in colliders(6.0) {
+                assert!(a.overlaps(&b), "{a:?} should overlap {b:?}");
+                assert!(b.overlaps(&a), "{b:?} should overlap {a:?}");
+            }
+            for b in colliders(20.0) {
+                assert!(!a.overlaps(&b), "{a:?} shouldn't overlap {b:?}");
+                assert!(!b.overlaps(&a), "{b:?} shouldn't overlap {a:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use super::Rect;
+
+/// A uniform grid broadphase. Items are bucketed by every cell their bounds touch,
+/// so finding what might overlap something only looks at nearby items
+/// instead of all of them.
+#[derive(Debug, Clone)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl SpatialGrid {
+    /// `cell_size` works best around the size of the largest item
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Builds a grid holding each of `bounds` by its index
+    pub fn build(cell_size: f32, bounds: impl IntoIterator<Item = Rect>) -> Self {
+        let mut grid = Self::new(cell_size);
+        for (index, bounds) in bounds.into_iter().enumerate() {
+            grid.insert(index, &bounds);
+        }
+        grid
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    pub fn insert(&mut self, index: usize, bounds: &Rect) {
+        for cell in self.cells_covering(bounds) {
+            self.cells.entry(cell).or_default().push(index);
+        }
+    }
+
+    /// Fills `results` with the sorted indices of items whose cells `bounds` touches.
+    /// These are only candidates, an exact overlap test is still needed.
+    pub fn query(&self, bounds: &Rect, results: &mut Vec<usize>) {
+        results.clear();
+        for cell in self.cells_covering(bounds) {
+            if let Some(items) = self.cells.get(&cell) {
+                results.extend(items);
+            }
+        }
+        // Items spanning several cells are found once per cell
+        results.sort_unstable();
+        results.dedup();
+    }
+
+    fn cells_covering(&self, bounds: &Rect) -> impl Iterator<Item = (i32, i32)> {
+        let cell = |v: f32| (v / self.cell_size).floor() as i32;
+        let (left, right) = (cell(bounds.x), cell(bounds.x + bounds.w));
+        let (top, bottom) = (cell(bounds.y), cell(bounds.y + bounds.h));
+        (left..=right).flat_map(move |x| (top..=bottom).map(move |y| (x, y)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn query_finds_nearby_items_once() {
+        let grid = SpatialGrid::build(
+            10.0,
+            [
+                Rect::new(0.0, 0.0, 25.0, 25.0),
+                Rect::new(100.0, 100.0, 5.0, 5.0),
+                Rect::new(-8.0, 12.0, 12.0, 4.0),
+            ],
+        );
+
+        let mut results = vec![];
+        grid.query(&Rect::new(5.0, 5.0, 15.0, 15.0), &mut results);
+        assert_eq!(vec![0, 2], results);
+
+        grid.query(&Rect::new(102.0, 99.0, 2.0, 2.0), &mut results);
+        assert_eq!(vec![1], results);
+
+        grid.query(&Rect::new(50.0, 50.0, 1.0, 1.0), &mut results);
+        assert!(results.is_empty());
+    }
+}
@@ -4,5 +4,17 @@ pub use circle::Circle;
 mod rect;
 pub use rect::Rect;
 
-mod position;
-pub use position::Position;
+mod vec2;
+pub use vec2::{Position, Vec2};
+
+mod grid;
+pub use grid::SpatialGrid;
+
+mod polygon;
+pub use polygon::Polygon;
+
+mod capsule;
+pub use capsule::Capsule;
+
+mod collider;
+pub use collider::Collider;
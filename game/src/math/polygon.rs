@@ -0,0 +1,128 @@
+use super::{Circle, Position, Rect, Vec2};
+
+/// A convex polygon, its points in order around the edge
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Polygon {
+    pub points: Vec<Position>,
+}
+
+impl Polygon {
+    pub fn new(points: Vec<Position>) -> Self {
+        Polygon { points }
+    }
+
+    /// Returns the smallest `Rect` containing every point
+    pub fn bounds(&self) -> Rect {
+        let (mut left, mut top) = (f32::MAX, f32::MAX);
+        let (mut right, mut bottom) = (f32::MIN, f32::MIN);
+        for point in &self.points {
+            left = left.min(point.x);
+            top = top.min(point.y);
+            right = right.max(point.x);
+            bottom = bottom.max(point.y);
+        }
+        Rect::new(left, top, right - left, bottom - top)
+    }
+
+    /// Translate every point by `offset` vector
+    pub fn offset(self, offset: Vec2) -> Polygon {
+        Polygon::new(self.points.into_iter().map(|p| p + offset).collect())
+    }
+
+    /// Returns each edge as its start and end point
+    pub fn edges(&self) -> impl Iterator<Item = (&Position, &Position)> {
+        self.points.iter().zip(self.points.iter().cycle().skip(1))
+    }
+
+    /// The unit normal of each edge, which are the axes a separating-axis test checks
+    pub fn axes(&self) -> impl Iterator<Item = Vec2> + '_ {
+        self.edges()
+            .map(|(a, b)| Vec2::new(a.y - b.y, b.x - a.x).normalize())
+            .filter(|axis| *axis != Vec2::ZERO)
+    }
+
+    /// The interval covered by the polygon along `axis`
+    pub fn project(&self, axis: Vec2) -> (f32, f32) {
+        self.points
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(min, max), p| {
+                let distance = p.dot(axis);
+                (min.min(distance), max.max(distance))
+            })
+    }
+
+    /// Checks whether the `Polygon` overlaps another `Polygon`, touching counts
+    pub fn overlaps(&self, other: &Polygon) -> bool {
+        self.axes()
+            .chain(other.axes())
+            .all(|axis| intervals_overlap(self.project(axis), other.project(axis)))
+    }
+
+    /// Checks whether the `Polygon` overlaps a `Circle`, touching counts
+    pub fn overlaps_circle(&self, circle: &Circle) -> bool {
+        let center = circle.point();
+        let Some(&closest) = self
+            .points
+            .iter()
+            .min_by(|a, b| a.distance(center).total_cmp(&b.distance(center)))
+        else {
+            return false;
+        };
+
+        // Past a corner the only axis that can separate them points at the centre
+        let corner_axis = (center - closest).normalize();
+        self.axes()
+            .chain(Some(corner_axis).filter(|axis| *axis != Vec2::ZERO))
+            .all(|axis| {
+                let projected = center.dot(axis);
+                intervals_overlap(
+                    self.project(axis),
+                    (projected - circle.r, projected + circle.r),
+                )
+            })
+    }
+}
+
+impl From<Rect> for Polygon {
+    fn from(rect: Rect) -> Self {
+        Polygon::new(vec![
+            Position::new(rect.left(), rect.top()),
+            Position::new(rect.right(), rect.top()),
+            Position::new(rect.right(), rect.bottom()),
+            Position::new(rect.left(), rect.bottom()),
+        ])
+    }
+}
+
+pub(super) fn intervals_overlap(a: (f32, f32), b: (f32, f32)) -> bool {
+    a.0 <= b.1 && b.0 <= a.1
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Circle, Polygon, Position, Rect};
+
+    fn triangle(x: f32, y: f32) -> Polygon {
+        Polygon::new(vec![
+            Position { x, y },
+            Position { x: x + 10.0, y },
+            Position { x, y: y + 10.0 },
+        ])
+    }
+
+    #[test]
+    fn polygon_overlaps() {
+        assert!(triangle(0.0, 0.0).overlaps(&triangle(4.0, 4.0)));
+        // Bounds overlap, but the long edges face each other with a gap
+        assert!(!triangle(0.0, 0.0).overlaps(&triangle(6.0, 6.0)));
+        assert!(triangle(0.0, 0.0).overlaps(&Rect::new(-5.0, -5.0, 6.0, 6.0).into()));
+    }
+
+    #[test]
+    fn polygon_overlaps_circle() {
+        assert!(triangle(0.0, 0.0).overlaps_circle(&Circle::new(8.0, 8.0, 4.5)));
+        assert!(!triangle(0.0, 0.0).overlaps_circle(&Circle::new(8.0, 8.0, 4.0)));
+        assert!(!triangle(0.0, 0.0).overlaps_circle(&Circle::new(-3.0, -3.0, 4.0)));
+        assert!(triangle(0.0, 0.0).overlaps_circle(&Circle::new(-2.0, -2.0, 3.0)));
+    }
+}
@@ -123,4 +123,60 @@ impl Rect {
     pub fn offset(self, offset: Position) -> Rect {
         Rect::new(self.x + offset.x, self.y + offset.y, self.w, self.h)
     }
+
+    /// Returns how far along `motion`, from 0 to 1, a ray from `origin` first touches the `Rect`.
+    /// A ray starting inside hits at 0.
+    pub fn ray_intersection(&self, origin: &Position, motion: &Position) -> Option<f32> {
+        let mut entry: f32 = 0.0;
+        let mut exit: f32 = 1.0;
+        for (start, delta, min, max) in [
+            (origin.x, motion.x, self.left(), self.right()),
+            (origin.y, motion.y, self.top(), self.bottom()),
+        ] {
+            if delta.abs() < f32::EPSILON {
+                if start < min || start > max {
+                    return None;
+                }
+                continue;
+            }
+            let near = (min - start) / delta;
+            let far = (max - start) / delta;
+            entry = entry.max(near.min(far));
+            exit = exit.min(near.max(far));
+            if entry > exit {
+                return None;
+            }
+        }
+        Some(entry)
+    }
+
+    /// Returns when, from 0 to 1, the `Rect` first overlaps `other` while moving by `motion`
+    pub fn sweep_rect(&self, motion: &Position, other: &Rect) -> Option<f32> {
+        // Shrinking self to its corner and growing other to match keeps the overlap the same
+        let expanded = Rect::new(
+            other.x - self.w,
+            other.y - self.h,
+            other.w + self.w,
+            other.h + self.h,
+        );
+        expanded.ray_intersection(&self.point(), motion)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sweep_rect_time_of_impact() {
+        let bullet = Rect::new(0.0, 100.0, 4.0, 4.0);
+        let enemy = Rect::new(0.0, 40.0, 8.0, 8.0);
+        let motion = Position { x: 0.0, y: -200.0 };
+        assert!(!bullet.offset(motion).overlaps(&enemy));
+        assert_eq!(Some(0.26), bullet.sweep_rect(&motion, &enemy));
+
+        let miss = Position { x: 50.0, y: -200.0 };
+        assert_eq!(None, bullet.sweep_rect(&miss, &enemy));
+        assert_eq!(Some(0.0), enemy.sweep_rect(&miss, &enemy));
+    }
 }
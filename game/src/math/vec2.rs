@@ -0,0 +1,169 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use serde::{Deserialize, Serialize};
+
+use crate::caffeinated_gorilla::space::types;
+
+/// A 2D vector, for directions and velocities as well as points
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// A point on the screen
+pub type Position = Vec2;
+
+impl Vec2 {
+    pub const ZERO: Vec2 = Vec2::new(0.0, 0.0);
+    /// Pointing up the screen
+    pub const UP: Vec2 = Vec2::new(0.0, -1.0);
+    /// Pointing down the screen
+    pub const DOWN: Vec2 = Vec2::new(0.0, 1.0);
+
+    pub const fn new(x: f32, y: f32) -> Self {
+        Vec2 { x, y }
+    }
+
+    /// A unit vector `angle` radians clockwise from the x axis
+    pub fn from_angle(angle: f32) -> Self {
+        Vec2::new(angle.cos(), angle.sin())
+    }
+
+    pub fn length(&self) -> f32 {
+        self.x.hypot(self.y)
+    }
+
+    pub fn distance(&self, other: Self) -> f32 {
+        (other - *self).length()
+    }
+
+    /// The same direction with a length of 1, or zero if there's no direction
+    pub fn normalize(self) -> Self {
+        let length = self.length();
+        if length > f32::EPSILON {
+            Vec2::new(self.x / length, self.y / length)
+        } else {
+            Vec2::ZERO
+        }
+    }
+
+    pub fn dot(self, other: Self) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// The point `t` of the way from here to `other`
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+
+    /// Turned `angle` radians clockwise, as y points down the screen
+    pub fn rotate(self, angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Vec2::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Vec2 {
+    fn add_assign(&mut self, other: Vec2) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl SubAssign for Vec2 {
+    fn sub_assign(&mut self, other: Vec2) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<f32> for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, scale: f32) -> Vec2 {
+        Vec2::new(self.x * scale, self.y * scale)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Vec2;
+
+    fn neg(self) -> Vec2 {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+impl From<Vec2> for types::Position {
+    fn from(value: Vec2) -> Self {
+        types::Position {
+            x: value.x,
+            y: value.y,
+        }
+    }
+}
+
+impl From<types::Position> for Vec2 {
+    fn from(value: types::Position) -> Self {
+        Vec2::new(value.x, value.y)
+    }
+}
+
+impl From<Vec2> for types::Size {
+    fn from(value: Vec2) -> Self {
+        types::Size {
+            width: value.x,
+            height: value.y,
+        }
+    }
+}
+
+impl From<types::Size> for Vec2 {
+    fn from(value: types::Size) -> Self {
+        Vec2::new(value.width, value.height)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::{Position, Vec2};
+
+    #[test]
+    fn position_distance() {
+        let first = Position { x: 1.0, y: 2.0 };
+        let second = Position { x: 4.0, y: 3.0 };
+        let distance = first.distance(second);
+        assert_eq!("3.16228", format!("{:5}", distance));
+    }
+
+    #[test]
+    fn vec2_operations() {
+        let a = Vec2::new(3.0, 4.0);
+        assert_eq!(5.0, a.length());
+        assert_eq!(Vec2::new(0.6, 0.8), a.normalize());
+        assert_eq!(Vec2::ZERO, Vec2::ZERO.normalize());
+        assert_eq!(Vec2::new(4.0, 6.0), a + Vec2::new(1.0, 2.0));
+        assert_eq!(Vec2::new(-3.0, -4.0), -a);
+        assert_eq!(11.0, a.dot(Vec2::new(1.0, 2.0)));
+        assert_eq!(Vec2::new(2.0, 2.0), a.lerp(Vec2::new(1.0, 0.0), 0.5));
+
+        let turned = Vec2::new(1.0, 0.0).rotate(FRAC_PI_2);
+        assert!((turned - Vec2::DOWN).length() < 1e-6);
+    }
+}
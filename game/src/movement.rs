@@ -0,0 +1,239 @@
+use std::f32::consts::TAU;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    math::{Position, Vec2},
+    state::Shape,
+    Screen,
+};
+
+/// How an enemy travels down the screen. All variants travel along the
+/// enemy's `velocity` unless noted otherwise, swaying to either side of it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub enum Movement {
+    #[default]
+    Straight,
+    /// Drifts down while swaying side to side `frequency` times a second
+    SineWeave { amplitude: f32, frequency: f32 },
+    /// Like `SineWeave`, but with sharp turns, taking `period` seconds per sweep
+    ZigZag { amplitude: f32, period: f32 },
+    /// Drifts down for `delay` seconds, then locks on and charges
+    /// where the player was at `speed_multiplier` times normal speed.
+    /// The charge replaces the enemy's `velocity` for good.
+    Dive { delay: f32, speed_multiplier: f32 },
+    /// Follows a cubic bezier over `duration` seconds at whatever pace the curve
+    /// sets, then continues along `velocity`. Points are fractions of the screen size.
+    Bezier {
+        points: [Position; 4],
+        duration: f32,
+    },
+    /// Descends to `hold_y` (a fraction of the screen height), strafes
+    /// for `strafe_time` seconds, then reverses `velocity` to retreat off the top of the screen
+    StrafeRetreat { hold_y: f32, strafe_time: f32 },
+}
+
+/// Per enemy state needed to follow a `Movement`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PathState {
+    /// Seconds since the current stage of movement began
+    pub age: f32,
+    /// Where the enemy was when the path started
+    pub origin: Position,
+    /// Locked in direction of travel, for dives
+    pub heading: Option<Vec2>,
+    /// Which step of a multi-stage movement is underway
+    pub stage: u32,
+}
+
+impl PathState {
+    pub fn new(origin: Position) -> Self {
+        Self {
+            origin,
+            ..Default::default()
+        }
+    }
+
+    fn next_stage(&mut self) {
+        self.stage += 1;
+        self.age = 0.0;
+    }
+}
+
+// A -1 to 1 triangle wave with a period of 1
+fn triangle_wave(t: f32) -> f32 {
+    4.0 * (t - (t + 0.5).floor()).abs() - 1.0
+}
+
+// Moves along `velocity`, then sideways to it by how much `offset` changed this frame,
+// so the sway follows the direction of travel rather than the screen
+fn advance_with_sway(
+    shape: &mut Shape,
+    path: &PathState,
+    frame_time: f32,
+    offset: impl Fn(f32) -> f32,
+) {
+    shape.advance(frame_time);
+    let forward = shape.velocity.normalize();
+    let side = Vec2::new(forward.y, -forward.x);
+    shape.position += side * (offset(path.age) - offset(path.age - frame_time));
+}
+
+fn cubic_bezier(points: &[Position; 4], t: f32) -> Position {
+    let u = 1.0 - t;
+    let weights = [u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t];
+    weights
+        .iter()
+        .zip(points)
+        .fold(Position::ZERO, |point, (&weight, &control)| {
+            point + control * weight
+        })
+}
+
+impl Movement {
+    pub fn update(
+        &self,
+        path: &mut PathState,
+        shape: &mut Shape,
+        target: &Position,
+        screen: &Screen,
+        frame_time: f32,
+    ) {
+        path.age += frame_time;
+
+        match self {
+            Movement::Straight => {
+                shape.advance(frame_time);
+            }
+            Movement::SineWeave {
+                amplitude,
+                frequency,
+            } => {
+                advance_with_sway(shape, path, frame_time, |age| {
+                    amplitude * (age * frequency * TAU).sin()
+                });
+            }
+            Movement::ZigZag { amplitude, period } => {
+                // Offset by a quarter period so the enemy starts at its origin
+                advance_with_sway(shape, path, frame_time, |age| {
+                    amplitude * triangle_wave(age / period + 0.25)
+                });
+            }
+            Movement::Dive {
+                delay,
+                speed_multiplier,
+            } => {
+                if path.age < *delay {
+                    shape.advance(frame_time);
+                    return;
+                }
+
+                if path.heading.is_none() {
+                    let heading = (*target - shape.position).normalize();
+                    path.heading = Some(heading);
+                    shape.velocity = heading * shape.speed() * *speed_multiplier;
+                }
+                shape.advance(frame_time);
+            }
+            Movement::Bezier { points, duration } => {
+                if path.age >= *duration {
+                    shape.advance(frame_time);
+                    return;
+                }
+
+                let point = cubic_bezier(points, path.age / duration);
+                shape.position.x = point.x * screen.width();
+                shape.position.y = point.y * screen.height();
+            }
+            Movement::StrafeRetreat {
+                hold_y,
+                strafe_time,
+            } => match path.stage {
+                0 => {
+                    shape.advance(frame_time);
+                    if shape.position.y >= hold_y * screen.height() {
+                        path.origin = shape.position;
+                        path.next_stage();
+                    }
+                }
+                1 => {
+                    let sweep = (screen.width() / 2.0 - shape.size).max(0.0);
+                    let center = screen.width() / 2.0;
+                    // Start the sweep from wherever the enemy stopped
+                    let phase = ((path.origin.x - center) / sweep.max(1.0))
+                        .clamp(-1.0, 1.0)
+                        .asin();
+                    shape.position.x = center + sweep * (phase + path.age).sin();
+                    if path.age >= *strafe_time {
+                        shape.velocity = -shape.velocity;
+                        path.next_stage();
+                    }
+                }
+                _ => {
+                    shape.advance(frame_time);
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{infrastructure::RecordingScreen, state::ShapeKind, ui::WHITE};
+
+    fn shape(velocity: Vec2) -> Shape {
+        Shape {
+            position: Position::ZERO,
+            previous_position: Position::ZERO,
+            velocity,
+            size: 32.0,
+            color: WHITE,
+            kind: ShapeKind::Circle,
+        }
+    }
+
+    fn run(movement: &Movement, shape: &mut Shape, seconds: u32) {
+        let screen = RecordingScreen::new(800.0, 600.0);
+        let mut path = PathState::new(shape.position);
+        for _ in 0..seconds * 60 {
+            movement.update(&mut path, shape, &Position::ZERO, &screen, 1.0 / 60.0);
+        }
+    }
+
+    #[test]
+    fn sway_follows_velocity() {
+        let weave = Movement::SineWeave {
+            amplitude: 20.0,
+            frequency: 0.25,
+        };
+        let mut down = shape(Vec2::DOWN * 100.0);
+        run(&weave, &mut down, 1);
+        assert!(down.position.distance(Vec2::new(20.0, 100.0)) < 0.01);
+
+        // Heading right, the same sway is up and down instead
+        let mut right = shape(Vec2::new(100.0, 0.0));
+        run(&weave, &mut right, 1);
+        assert!(right.position.distance(Vec2::new(100.0, -20.0)) < 0.01);
+    }
+
+    #[test]
+    fn strafe_retreat_reverses_velocity() {
+        let strafe = Movement::StrafeRetreat {
+            hold_y: 0.1,
+            strafe_time: 0.5,
+        };
+        let mut enemy = shape(Vec2::DOWN * 120.0);
+        run(&strafe, &mut enemy, 2);
+        assert_eq!(Vec2::UP * 120.0, enemy.velocity);
+        assert!(enemy.position.y < 60.0);
+    }
+
+    #[test]
+    fn triangle_wave_range() {
+        assert_eq!(-1.0, triangle_wave(0.0));
+        assert_eq!(1.0, triangle_wave(0.5));
+        assert_eq!(-1.0, triangle_wave(1.0));
+        assert_eq!(0.0, triangle_wave(0.25));
+    }
+}
@@ -0,0 +1,219 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use rand::{thread_rng, Rng};
+
+use crate::{
+    math::{Position, Vec2},
+    ui::{Camera, Color, ORANGE, RED, WHITE, YELLOW},
+};
+
+/// Describes how the particles for an effect are spawned and age
+#[derive(Debug)]
+pub struct ParticleEffect {
+    /// Particles created by a single burst
+    pub count: u32,
+    /// Ranges are (min, max), with each particle picking a value between
+    pub speed: (f32, f32),
+    pub lifetime: (f32, f32),
+    pub size: (f32, f32),
+    /// Center of the emission arc, in radians
+    pub direction: f32,
+    /// Width of the emission arc, in radians. TAU emits in every direction.
+    pub spread: f32,
+    /// Fraction of velocity lost per second
+    pub drag: f32,
+    pub start_color: Color,
+    pub end_color: Color,
+    pub is_circle: bool,
+}
+
+pub const EXPLOSION: ParticleEffect = ParticleEffect {
+    count: 24,
+    speed: (40.0, 180.0),
+    lifetime: (0.3, 0.8),
+    size: (3.0, 7.0),
+    direction: 0.0,
+    spread: TAU,
+    drag: 2.0,
+    start_color: YELLOW,
+    end_color: RED,
+    is_circle: false,
+};
+
+pub const IMPACT: ParticleEffect = ParticleEffect {
+    count: 6,
+    speed: (30.0, 90.0),
+    lifetime: (0.1, 0.25),
+    size: (2.0, 3.0),
+    direction: FRAC_PI_2,
+    spread: 2.0,
+    drag: 4.0,
+    start_color: WHITE,
+    end_color: ORANGE,
+    is_circle: true,
+};
+
+pub const PLAYER_DEATH: ParticleEffect = ParticleEffect {
+    count: 60,
+    speed: (20.0, 240.0),
+    lifetime: (0.6, 1.5),
+    size: (3.0, 9.0),
+    direction: 0.0,
+    spread: TAU,
+    drag: 1.5,
+    start_color: WHITE,
+    end_color: YELLOW,
+    is_circle: true,
+};
+
+pub const EXHAUST: ParticleEffect = ParticleEffect {
+    count: 1,
+    speed: (60.0, 120.0),
+    lifetime: (0.15, 0.35),
+    size: (2.0, 5.0),
+    direction: FRAC_PI_2,
+    spread: 0.5,
+    drag: 3.0,
+    start_color: YELLOW,
+    end_color: RED,
+    is_circle: true,
+};
+
+#[derive(Debug, Clone)]
+pub struct Particle {
+    pub position: Position,
+    pub velocity: Vec2,
+    pub age: f32,
+    pub lifetime: f32,
+    pub size: f32,
+    pub drag: f32,
+    pub start_color: Color,
+    pub end_color: Color,
+    pub is_circle: bool,
+}
+
+impl Particle {
+    fn life_fraction(&self) -> f32 {
+        (self.age / self.lifetime).min(1.0)
+    }
+
+    /// Blends from the start to end color, fading out as the particle ages
+    pub fn color(&self) -> Color {
+        let t = self.life_fraction();
+        let mut color = self.start_color.lerp(&self.end_color, t);
+        color.a *= 1.0 - t;
+        color
+    }
+}
+
+/// Continuously emits an effect at `rate` particles per second
+#[derive(Debug, Clone)]
+pub struct Emitter {
+    pub effect: &'static ParticleEffect,
+    pub rate: f32,
+    accumulator: f32,
+}
+
+impl Emitter {
+    pub const fn new(effect: &'static ParticleEffect, rate: f32) -> Self {
+        Self {
+            effect,
+            rate,
+            accumulator: 0.0,
+        }
+    }
+}
+
+/// Purely cosmetic, so none of this is saved across hot reloads
+#[derive(Debug, Clone)]
+pub struct ParticleSystem {
+    pub particles: Vec<Particle>,
+    pub exhaust: Emitter,
+}
+
+impl Default for ParticleSystem {
+    fn default() -> Self {
+        Self {
+            particles: vec![],
+            exhaust: Emitter::new(&EXHAUST, 40.0),
+        }
+    }
+}
+
+fn random_between(rng: &mut impl Rng, range: (f32, f32)) -> f32 {
+    range.0 + (range.1 - range.0) * rng.gen::<f32>()
+}
+
+impl ParticleSystem {
+    pub fn burst(&mut self, effect: &ParticleEffect, position: &Position) {
+        self.burst_with_color(effect, position, effect.start_color.clone());
+    }
+
+    /// Like `burst`, but starting from `start_color` instead of the effect's own
+    pub fn burst_with_color(
+        &mut self,
+        effect: &ParticleEffect,
+        position: &Position,
+        start_color: Color,
+    ) {
+        for _ in 0..effect.count {
+            self.spawn(effect, position, start_color.clone());
+        }
+    }
+
+    fn spawn(&mut self, effect: &ParticleEffect, position: &Position, start_color: Color) {
+        let mut rng = thread_rng();
+        let angle = effect.direction + effect.spread * (rng.gen::<f32>() - 0.5);
+        let speed = random_between(&mut rng, effect.speed);
+        self.particles.push(Particle {
+            position: *position,
+            velocity: Vec2::from_angle(angle) * speed,
+            age: 0.0,
+            lifetime: random_between(&mut rng, effect.lifetime),
+            size: random_between(&mut rng, effect.size),
+            drag: effect.drag,
+            start_color,
+            end_color: effect.end_color.clone(),
+            is_circle: effect.is_circle,
+        });
+    }
+
+    pub fn emit_exhaust(&mut self, position: &Position, frame_time: f32) {
+        self.exhaust.accumulator += self.exhaust.rate * frame_time;
+        while self.exhaust.accumulator >= 1.0 {
+            self.exhaust.accumulator -= 1.0;
+            let effect = self.exhaust.effect;
+            self.burst(effect, position);
+        }
+    }
+
+    pub fn update(&mut self, frame_time: f32) {
+        for particle in &mut self.particles {
+            particle.age += frame_time;
+            let drag = (1.0 - particle.drag * frame_time).max(0.0);
+            particle.velocity = particle.velocity * drag;
+            particle.position += particle.velocity * frame_time;
+        }
+        self.particles.retain(|p| p.age < p.lifetime);
+    }
+
+    pub fn draw(&self, camera: &Camera) {
+        for particle in &self.particles {
+            let color = particle.color();
+            if particle.is_circle {
+                camera.draw_circle(particle.position.into(), particle.size / 2.0, color.into());
+            } else {
+                let half_size = particle.size / 2.0;
+                camera.draw_rectangle(
+                    (
+                        particle.position.x - half_size,
+                        particle.position.y - half_size,
+                    )
+                        .into(),
+                    (particle.size, particle.size).into(),
+                    color.into(),
+                );
+            }
+        }
+    }
+}
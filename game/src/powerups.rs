@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    math::{Position, Vec2},
+    state::{Shape, ShapeKind},
+    ui::{Color, AQUA, BLUE, ORANGE, WHITE},
+};
+
+const FALL_SPEED: f32 = 60.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PowerUpKind {
+    SpreadShot,
+    RapidFire,
+    Piercing,
+    Shield,
+}
+
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 4] = [
+        PowerUpKind::SpreadShot,
+        PowerUpKind::RapidFire,
+        PowerUpKind::Piercing,
+        PowerUpKind::Shield,
+    ];
+
+    /// How long the power-up lasts once collected, in seconds
+    pub fn duration(&self) -> f32 {
+        match self {
+            PowerUpKind::SpreadShot => 10.0,
+            PowerUpKind::RapidFire => 8.0,
+            PowerUpKind::Piercing => 8.0,
+            PowerUpKind::Shield => 20.0,
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            PowerUpKind::SpreadShot => ORANGE,
+            PowerUpKind::RapidFire => WHITE,
+            PowerUpKind::Piercing => BLUE,
+            PowerUpKind::Shield => AQUA,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PowerUpKind::SpreadShot => "Spread",
+            PowerUpKind::RapidFire => "Rapid",
+            PowerUpKind::Piercing => "Pierce",
+            PowerUpKind::Shield => "Shield",
+        }
+    }
+}
+
+/// A pickup drifting down the screen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerUp {
+    pub shape: Shape,
+    pub kind: PowerUpKind,
+    pub collected: bool,
+}
+
+impl PowerUp {
+    pub fn new(position: Position, kind: PowerUpKind) -> Self {
+        Self {
+            shape: Shape {
+                previous_position: position,
+                position,
+                velocity: Vec2::DOWN * FALL_SPEED,
+                size: 20.0,
+                color: kind.color(),
+                kind: ShapeKind::Circle,
+            },
+            kind,
+            collected: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivePowerUp {
+    pub kind: PowerUpKind,
+    /// Seconds until the power-up wears off
+    pub remaining: f32,
+}
+
+/// Power-ups the player currently has
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActivePowerUps {
+    active: Vec<ActivePowerUp>,
+}
+
+impl ActivePowerUps {
+    /// Starts a power-up, or refreshes its timer if already active
+    pub fn activate(&mut self, kind: PowerUpKind) {
+        match self.active.iter_mut().find(|p| p.kind == kind) {
+            Some(active) => active.remaining = kind.duration(),
+            None => self.active.push(ActivePowerUp {
+                kind,
+                remaining: kind.duration(),
+            }),
+        }
+    }
+
+    pub fn is_active(&self, kind: PowerUpKind) -> bool {
+        self.active.iter().any(|p| p.kind == kind)
+    }
+
+    /// Removes a power-up, returning if it was active
+    pub fn consume(&mut self, kind: PowerUpKind) -> bool {
+        let count = self.active.len();
+        self.active.retain(|p| p.kind != kind);
+        count != self.active.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+
+    pub fn update(&mut self, frame_time: f32) {
+        for power_up in &mut self.active {
+            power_up.remaining -= frame_time;
+        }
+        self.active.retain(|p| p.remaining > 0.0);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ActivePowerUp> {
+        self.active.iter()
+    }
+}
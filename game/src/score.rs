@@ -1,43 +1,443 @@
-use std::fs;
+use std::{
+    cmp::Reverse,
+    env, fmt,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use serde::{Deserialize, Serialize};
 
+/// How many entries the high score table keeps
+pub const TABLE_SIZE: usize = 10;
+pub const INITIALS_LENGTH: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScoreEntry {
+    pub initials: String,
+    pub score: u64,
+    /// When the score was set, in seconds since the Unix epoch
+    pub date: u64,
+    pub wave: u32,
+}
+
+impl HighScoreEntry {
+    /// The date as YYYY-MM-DD, or dashes for scores from before dates were kept
+    pub fn date_string(&self) -> String {
+        if self.date == 0 {
+            return "----------".to_string();
+        }
+
+        // Days since the epoch to a civil date, from Howard Hinnant's date algorithms
+        let days = (self.date / 86400) as i64 + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days.rem_euclid(146097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        format!("{year:04}-{month:02}-{day:02}")
+    }
+}
+
+const SCORE_FILE: &str = "highscore.dat";
+/// Where scores are kept, defaulting to the working directory
+pub const DATA_DIR_ENV: &str = "SPACE_SHOOTER_DATA_DIR";
+
+// Version 0 was a bare number and version 1 had no version or checksum fields
+const SCORE_FILE_VERSION: u64 = 2;
+
 #[derive(Debug, Serialize, Deserialize)]
+struct ScoreFile {
+    version: u64,
+    /// FNV-1a hash of `entries` as JSON, to catch partial writes and hand edits
+    checksum: u64,
+    entries: Vec<HighScoreEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScoreFileV1 {
+    entries: Vec<HighScoreEntry>,
+}
+
+#[derive(Debug)]
+pub enum ScoreError {
+    Io(io::Error),
+    Corrupt(String),
+    UnsupportedVersion(u64),
+}
+
+impl fmt::Display for ScoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScoreError::Io(e) => write!(f, "{e}"),
+            ScoreError::Corrupt(reason) => write!(f, "file is corrupt ({reason})"),
+            ScoreError::UnsupportedVersion(version) => {
+                write!(f, "file is from a newer version ({version})")
+            }
+        }
+    }
+}
+
+impl From<io::Error> for ScoreError {
+    fn from(e: io::Error) -> Self {
+        ScoreError::Io(e)
+    }
+}
+
+fn checksum(entries: &[HighScoreEntry]) -> u64 {
+    let json = serde_json::to_string(entries).unwrap_or_default();
+    json.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn serialize_score_file(entries: &[HighScoreEntry]) -> String {
+    let file = ScoreFile {
+        version: SCORE_FILE_VERSION,
+        checksum: checksum(entries),
+        entries: entries.to_vec(),
+    };
+    serde_json::to_string_pretty(&file).expect("Unable to serialize high scores")
+}
+
+fn parse_score_file(contents: &str) -> Result<Vec<HighScoreEntry>, ScoreError> {
+    if let Ok(score) = contents.trim().parse::<u64>() {
+        return Ok(if score > 0 {
+            vec![HighScoreEntry {
+                initials: "???".to_string(),
+                score,
+                date: 0,
+                wave: 0,
+            }]
+        } else {
+            vec![]
+        });
+    }
+
+    let value: serde_json::Value =
+        serde_json::from_str(contents).map_err(|e| ScoreError::Corrupt(e.to_string()))?;
+    let Some(version) = value.get("version") else {
+        let file: ScoreFileV1 =
+            serde_json::from_value(value).map_err(|e| ScoreError::Corrupt(e.to_string()))?;
+        return Ok(file.entries);
+    };
+
+    let version = version
+        .as_u64()
+        .ok_or_else(|| ScoreError::Corrupt(format!("version {version} is not a number")))?;
+    if version > SCORE_FILE_VERSION {
+        return Err(ScoreError::UnsupportedVersion(version));
+    }
+    // Older versions never had a version field, so anything else was never written by the game
+    if version != SCORE_FILE_VERSION {
+        return Err(ScoreError::Corrupt(format!("unknown version {version}")));
+    }
+    let file: ScoreFile =
+        serde_json::from_value(value).map_err(|e| ScoreError::Corrupt(e.to_string()))?;
+    if file.checksum != checksum(&file.entries) {
+        return Err(ScoreError::Corrupt("checksum mismatch".to_string()));
+    }
+    Ok(file.entries)
+}
+
+/// Where `file` is kept, alongside the other saved data
+pub fn data_path(file: &str) -> PathBuf {
+    env::var_os(DATA_DIR_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(file)
+}
+
+/// Writes to a temporary file first, so a crash mid save leaves the old file intact
+pub fn write_data_file(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let temp = path.with_extension("tmp");
+    let mut file = File::create(&temp)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&temp, path)
+}
+
+fn write_score_file(entries: &[HighScoreEntry]) -> Result<(), ScoreError> {
+    write_data_file(&data_path(SCORE_FILE), &serialize_score_file(entries))?;
+    Ok(())
+}
+
+fn read_score_file(path: &Path) -> Result<Vec<HighScoreEntry>, ScoreError> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+
+    parse_score_file(&contents).inspect_err(|e| {
+        // Keep the unreadable file around rather than overwriting it on the next save.
+        // A file from a newer version is fine, it just isn't this build's to touch.
+        if matches!(e, ScoreError::Corrupt(_)) {
+            _ = fs::rename(path, path.with_extension("dat.bad"));
+        }
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HighScore {
     score: u64,
-    high_score: u64,
+    /// Best scores first, at most `TABLE_SIZE` of them
+    #[serde(default)]
+    table: Vec<HighScoreEntry>,
+    /// Never read or written to disk, for headless runs
+    #[serde(default)]
+    in_memory: bool,
+    /// The last problem loading or saving, for showing to the player
+    #[serde(default)]
+    error: Option<String>,
 }
 
-const SCORE_FILE: &str = "highscore.dat";
+pub fn current_date() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
 
 impl HighScore {
     pub fn load() -> Self {
-        let high_score = fs::read_to_string(SCORE_FILE)
-            .map_or(Ok(0), |i| i.parse::<u64>())
-            .unwrap_or(0);
+        Self::load_from(&data_path(SCORE_FILE))
+    }
+
+    fn load_from(path: &Path) -> Self {
+        let mut in_memory = false;
+        let (mut table, error) = match read_score_file(path) {
+            Ok(table) => (table, None),
+            Err(ScoreError::Io(e)) => (
+                vec![],
+                Some(format!("High scores could not be loaded: {e}")),
+            ),
+            Err(e @ ScoreError::UnsupportedVersion(_)) => {
+                // Saving would replace the newer file with this build's format
+                in_memory = true;
+                (
+                    vec![],
+                    Some(format!(
+                        "High scores could not be loaded, the {e}. New scores won't be saved"
+                    )),
+                )
+            }
+            Err(e) => (
+                vec![],
+                Some(format!(
+                    "High scores could not be loaded, the {e}. It was kept as {SCORE_FILE}.bad"
+                )),
+            ),
+        };
+        table.sort_by_key(|e| Reverse(e.score));
+        table.truncate(TABLE_SIZE);
+
+        HighScore {
+            score: 0,
+            table,
+            in_memory,
+            error,
+        }
+    }
+
+    pub fn in_memory() -> Self {
+        HighScore {
+            score: 0,
+            table: vec![],
+            in_memory: true,
+            error: None,
+        }
+    }
 
+    /// The same table with the current score back at zero
+    pub fn new_game(&self) -> Self {
         HighScore {
             score: 0,
-            high_score,
+            ..self.clone()
         }
     }
 
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
     pub fn current_score(&self) -> u64 {
         self.score
     }
 
     pub fn high_score(&self) -> u64 {
-        self.high_score
+        self.table.first().map_or(0, |e| e.score).max(self.score)
+    }
+
+    pub fn entries(&self) -> &[HighScoreEntry] {
+        &self.table
     }
 
     pub fn add(&mut self, addition: u64) {
         self.score += addition;
-        if self.score > self.high_score {
-            self.high_score = self.score;
+    }
+
+    /// If the current score is good enough for the table
+    pub fn qualifies(&self) -> bool {
+        self.score > 0
+            && (self.table.len() < TABLE_SIZE
+                || self.table.last().is_some_and(|e| self.score > e.score))
+    }
+
+    /// Adds the current score to the table and saves it, returning its rank from 0.
+    /// Ties go below existing entries, so the earlier score keeps its place.
+    pub fn record(&mut self, initials: &str, wave: u32, date: u64) -> Option<usize> {
+        if !self.qualifies() {
+            return None;
         }
+
+        let rank = self
+            .table
+            .iter()
+            .position(|e| self.score > e.score)
+            .unwrap_or(self.table.len());
+        self.table.insert(
+            rank,
+            HighScoreEntry {
+                initials: initials.to_string(),
+                score: self.score,
+                date,
+                wave,
+            },
+        );
+        self.table.truncate(TABLE_SIZE);
+        self.save();
+        Some(rank)
+    }
+
+    fn save(&mut self) {
+        if self.in_memory {
+            return;
+        }
+        self.error = write_score_file(&self.table)
+            .err()
+            .map(|e| format!("High scores could not be saved: {e}"));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn legacy_score_file_loads() {
+        let table = parse_score_file("1234").unwrap();
+        assert_eq!(1, table.len());
+        assert_eq!(1234, table[0].score);
+        assert!(parse_score_file("0").unwrap().is_empty());
+
+        let table = parse_score_file(
+            r#"{"entries": [{"initials": "AAA", "score": 5, "date": 0, "wave": 1}]}"#,
+        )
+        .unwrap();
+        assert_eq!(5, table[0].score);
+    }
+
+    #[test]
+    fn score_file_checksum() {
+        let entries = vec![HighScoreEntry {
+            initials: "AAA".to_string(),
+            score: 500,
+            date: 0,
+            wave: 2,
+        }];
+        let contents = serialize_score_file(&entries);
+        assert_eq!(500, parse_score_file(&contents).unwrap()[0].score);
+
+        let tampered = contents.replace("500", "900");
+        assert!(matches!(
+            parse_score_file(&tampered),
+            Err(ScoreError::Corrupt(_))
+        ));
+        assert!(matches!(
+            parse_score_file("garbage"),
+            Err(ScoreError::Corrupt(_))
+        ));
+    }
+
+    #[test]
+    fn only_later_versions_are_unsupported() {
+        let file =
+            |version: &str| format!(r#"{{"version": {version}, "checksum": 0, "entries": []}}"#);
+        assert!(matches!(
+            parse_score_file(&file("4294967298")),
+            Err(ScoreError::UnsupportedVersion(4294967298))
+        ));
+        for version in ["0", "1", "null", "\"2\"", "-1"] {
+            assert!(
+                matches!(
+                    parse_score_file(&file(version)),
+                    Err(ScoreError::Corrupt(_))
+                ),
+                "version {version}"
+            );
+        }
+    }
+
+    #[test]
+    fn newer_score_file_is_left_alone() {
+        let dir = env::temp_dir().join(format!("space-shooter-scores-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(SCORE_FILE);
+        let newer = r#"{"version": 99, "checksum": 0, "entries": []}"#;
+        fs::write(&path, newer).unwrap();
+
+        let mut high_score = HighScore::load_from(&path);
+        assert!(high_score.error().is_some());
+        high_score.score = 100;
+        high_score.record("AAA", 1, 0);
+        assert_eq!(newer, fs::read_to_string(&path).unwrap());
+
+        fs::write(&path, "garbage").unwrap();
+        HighScore::load_from(&path);
+        assert!(!path.exists());
+        assert!(path.with_extension("dat.bad").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn record_keeps_table_sorted_and_capped() {
+        let mut high_score = HighScore::in_memory();
+        for score in 1..=12 {
+            high_score.score = score * 10;
+            high_score.record("AAA", 1, 0);
+        }
+        assert_eq!(TABLE_SIZE, high_score.entries().len());
+        assert_eq!(120, high_score.entries()[0].score);
+        assert_eq!(30, high_score.entries()[9].score);
+
+        high_score.score = 20;
+        assert!(!high_score.qualifies());
+        high_score.score = 55;
+        assert_eq!(Some(7), high_score.record("BBB", 2, 0));
     }
 
-    pub fn save(&mut self) {
-        _ = fs::write(SCORE_FILE, self.high_score.to_string());
+    #[test]
+    fn date_string() {
+        let entry = HighScoreEntry {
+            initials: "AAA".to_string(),
+            score: 1,
+            date: 1_700_000_000,
+            wave: 1,
+        };
+        assert_eq!("2023-11-14", entry.date_string());
     }
 }
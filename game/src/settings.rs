@@ -0,0 +1,170 @@
+use std::{fs, io};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bindings::{Bindings, ControlScheme},
+    score::{data_path, write_data_file},
+    tuning::Tuning,
+};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+pub const MIN_HUD_SCALE: f32 = 0.75;
+pub const MAX_HUD_SCALE: f32 = 1.5;
+pub const HUD_SCALE_STEP: f32 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+
+    /// The tuning games start with
+    pub fn tuning(&self) -> Tuning {
+        let normal = Tuning::default();
+        match self {
+            Difficulty::Easy => Tuning {
+                enemy_fire_rate: 2.0,
+                spawn_rate: 0.75,
+                ..normal
+            },
+            Difficulty::Normal => normal,
+            Difficulty::Hard => Tuning {
+                enemy_fire_rate: 1.1,
+                spawn_rate: 1.3,
+                ..normal
+            },
+        }
+    }
+}
+
+/// Player preferences, saved to `settings.json` next to the high scores
+/// whenever they change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub difficulty: Difficulty,
+    /// Whether the scrolling star background is drawn
+    pub starfield: bool,
+    pub bindings: Bindings,
+    /// How large the HUD is drawn, 1 being its normal size
+    pub hud_scale: f32,
+    /// Only read from settings saved before keys could be rebound
+    #[serde(skip_serializing)]
+    controls: Option<ControlScheme>,
+    /// Never read or written to disk, for tests and headless runs
+    #[serde(skip)]
+    in_memory: bool,
+    /// The last problem loading or saving, for showing to the player
+    #[serde(skip)]
+    error: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            difficulty: Difficulty::default(),
+            starfield: true,
+            bindings: Bindings::default(),
+            hud_scale: 1.0,
+            controls: None,
+            in_memory: false,
+            error: None,
+        }
+    }
+}
+
+fn parse_settings(contents: &str) -> serde_json::Result<Settings> {
+    let mut settings: Settings = serde_json::from_str(contents)?;
+    settings.hud_scale = settings.hud_scale.clamp(MIN_HUD_SCALE, MAX_HUD_SCALE);
+    if let Some(scheme) = settings.controls.take() {
+        settings.bindings = scheme.bindings();
+    }
+    settings.bindings.fill_missing();
+    Ok(settings)
+}
+
+impl Settings {
+    /// Missing settings are the defaults, as are any that can't be read
+    pub fn load() -> Self {
+        let contents = match fs::read_to_string(data_path(SETTINGS_FILE)) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Settings::default(),
+            Err(e) => return Settings::with_error(format!("Settings could not be loaded: {e}")),
+        };
+        parse_settings(&contents).unwrap_or_else(|e| {
+            Settings::with_error(format!(
+                "Settings could not be loaded, the file is corrupt ({e})"
+            ))
+        })
+    }
+
+    pub fn in_memory() -> Self {
+        Settings {
+            in_memory: true,
+            ..Settings::default()
+        }
+    }
+
+    fn with_error(error: String) -> Self {
+        Settings {
+            error: Some(error),
+            ..Settings::default()
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn save(&mut self) {
+        if self.in_memory {
+            return;
+        }
+        let contents = serde_json::to_string_pretty(self).expect("Unable to serialize settings");
+        self.error = write_data_file(&data_path(SETTINGS_FILE), &contents)
+            .err()
+            .map(|e| format!("Settings could not be saved: {e}"));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn missing_settings_are_defaults() {
+        let settings = parse_settings(r#"{"difficulty": "Hard", "hud_scale": 9.0}"#).unwrap();
+        assert_eq!(Difficulty::Hard, settings.difficulty);
+        assert_eq!(Bindings::default(), settings.bindings);
+        assert!(settings.starfield);
+        assert_eq!(MAX_HUD_SCALE, settings.hud_scale);
+
+        let saved = serde_json::to_string(&settings).unwrap();
+        assert_eq!(Difficulty::Hard, parse_settings(&saved).unwrap().difficulty);
+        assert!(parse_settings("garbage").is_err());
+    }
+
+    #[test]
+    fn old_control_scheme_becomes_bindings() {
+        let settings = parse_settings(r#"{"controls": "Wasd"}"#).unwrap();
+        assert_eq!(ControlScheme::Wasd.bindings(), settings.bindings);
+        assert!(!serde_json::to_string(&settings)
+            .unwrap()
+            .contains("controls"));
+    }
+}
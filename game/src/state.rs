@@ -1,22 +1,91 @@
+use std::f32::consts::TAU;
+
 use crate::{
-    math::{Circle, Position, Rect},
+    bindings::Action,
+    boss::{Boss, BossSchedule},
+    combo::{Combo, ScorePopup},
+    enemies::EnemyKind,
+    math::{Capsule, Circle, Collider, Polygon, Position, Rect, Vec2},
+    movement::{Movement, PathState},
+    particles::{ParticleSystem, IMPACT, PLAYER_DEATH},
+    powerups::{ActivePowerUps, PowerUp, PowerUpKind},
     score::HighScore,
-    ui::{Color, AQUA, BLUE, RED, WHITE, YELLOW},
+    settings::Settings,
+    stats::{DeathCause, RunStats},
+    tuning::Tuning,
+    ui::{Color, ScreenShake, WidgetState, AQUA, BLUE, ORANGE, RED, WHITE, YELLOW},
+    waves::WaveProgress,
     Screen,
 };
 
-use rand::{seq::SliceRandom, thread_rng, Rng};
+use rand::{seq::SliceRandom, thread_rng, Rng, SeedableRng};
+use rand_pcg::Pcg32;
 use serde::{Deserialize, Serialize};
 
 pub const MOVEMENT_SPEED: f32 = 200.0;
+pub const ENEMY_BULLET_SPEED: f32 = 180.0;
+const PLAYER_BULLET_SPEED: f32 = MOVEMENT_SPEED * 2.0;
+pub const STARTING_LIVES: u32 = 3;
+
+// After losing a life the player is hidden for RESPAWN_TIME seconds,
+// then can't be hit for another INVULNERABLE_TIME seconds
+const RESPAWN_TIME: f32 = 1.0;
+const INVULNERABLE_TIME: f32 = 2.5;
+// Grace period after a shield absorbs a hit
+const SHIELD_BREAK_TIME: f32 = 1.0;
+
+// Screen shake trauma added by each kind of hit, out of a maximum of 1
+const PLAYER_HIT_TRAUMA: f32 = 0.6;
+const SHIELD_BREAK_TRAUMA: f32 = 0.3;
+const BOSS_DEATH_TRAUMA: f32 = 1.0;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "SavedShape")]
 pub struct Shape {
     pub position: Position,
-    pub speed: f32,
+    /// Where the shape was at the start of the last simulation step,
+    /// rendering blends between this and `position`
+    pub previous_position: Position,
+    /// In pixels per second
+    pub velocity: Vec2,
     pub size: f32,
     pub color: Color,
-    pub is_circle: bool,
+    #[serde(default)]
+    pub kind: ShapeKind,
+}
+
+/// A `Shape` as saved, which before velocities had a speed straight down the screen
+#[derive(Deserialize)]
+struct SavedShape {
+    position: Position,
+    /// Missing from states saved before rendering was interpolated
+    #[serde(default)]
+    previous_position: Option<Position>,
+    #[serde(default)]
+    velocity: Option<Vec2>,
+    #[serde(default)]
+    speed: Option<f32>,
+    size: f32,
+    color: Color,
+    #[serde(default)]
+    kind: ShapeKind,
+}
+
+impl From<SavedShape> for Shape {
+    fn from(saved: SavedShape) -> Self {
+        let velocity = saved
+            .velocity
+            .or(saved.speed.map(|speed| Vec2::DOWN * speed))
+            .unwrap_or(Vec2::ZERO);
+        Shape {
+            position: saved.position,
+            previous_position: saved.previous_position.unwrap_or(saved.position),
+            velocity,
+            size: saved.size,
+            color: saved.color,
+            kind: saved.kind,
+        }
+    }
 }
 
 impl Shape {
@@ -32,170 +101,849 @@ impl Shape {
             .clamp(half_width, screen.height() - half_width);
     }
 
+    pub fn store_previous_position(&mut self) {
+        self.previous_position = self.position;
+    }
+
+    /// Where the shape is `alpha` of the way through the current step, for drawing
+    pub fn position_at(&self, alpha: f32) -> Position {
+        self.previous_position.lerp(self.position, alpha)
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.velocity.length()
+    }
+
+    /// Moves along `velocity` for `frame_time` seconds
+    pub fn advance(&mut self, frame_time: f32) {
+        self.position += self.velocity * frame_time;
+    }
+
     pub fn half_width(&self) -> f32 {
         self.size / 2.0
     }
 
     pub fn upper_left(&self) -> Position {
         let half_width = self.half_width();
-
-        Position {
-            x: self.position.x - half_width,
-            y: self.position.y - half_width,
-        }
+        self.position - Vec2::new(half_width, half_width)
     }
 
     pub fn rect(&self) -> Rect {
-        Rect {
-            x: self.position.x - self.half_width(),
-            y: self.position.y - self.half_width(),
-            w: self.size,
-            h: self.size,
-        }
+        let upper_left = self.upper_left();
+        Rect::new(upper_left.x, upper_left.y, self.size, self.size)
     }
 
     pub fn circle(&self) -> Circle {
-        Circle {
-            x: self.position.x - self.half_width(),
-            y: self.position.y - self.half_width(),
-            r: self.size,
+        Circle::new(self.position.x, self.position.y, self.half_width())
+    }
+
+    pub fn collider(&self) -> Collider {
+        self.collider_at(self.position)
+    }
+
+    /// The collider if the shape were moved to `position`
+    pub fn collider_at(&self, position: Position) -> Collider {
+        let offset = position - self.position;
+        match &self.kind {
+            ShapeKind::Rect => Collider::Rect(self.rect().offset(offset)),
+            ShapeKind::Circle => Collider::Circle(self.circle().offset(offset)),
+            ShapeKind::Polygon(points) => Collider::Polygon(Polygon::new(
+                points
+                    .iter()
+                    .map(|&point| position + point * self.size)
+                    .collect(),
+            )),
+            ShapeKind::Capsule { length } => {
+                // Lined up with the direction of travel, with the rounded ends
+                // taking up half the width each
+                let mut direction = self.velocity.normalize();
+                if direction == Vec2::ZERO {
+                    direction = Vec2::UP;
+                }
+                let half_line = direction * ((length - 1.0).max(0.0) * self.half_width());
+                Collider::Capsule(Capsule::new(
+                    position - half_line,
+                    position + half_line,
+                    self.half_width(),
+                ))
+            }
         }
     }
 
+    /// A box around everything `collides_with` tests, for the broadphase
+    pub fn bounds(&self) -> Rect {
+        self.collider().bounds()
+    }
+
     pub fn collides_with(&self, other: &Self) -> bool {
-        if self.is_circle {
-            self.circle().overlaps_rect(&other.rect())
-        } else {
-            self.rect().overlaps(&other.rect())
+        self.collider().overlaps(&other.collider())
+    }
+
+    // From the current position back to where this step started
+    fn step_offset(&self) -> Vec2 {
+        self.previous_position - self.position
+    }
+
+    /// `bounds` covering the whole move since the start of the step
+    pub fn swept_bounds(&self) -> Rect {
+        self.bounds()
+            .combine_with(self.bounds().offset(self.step_offset()))
+    }
+
+    /// Like `collides_with`, but over the whole move since the start of the step so fast
+    /// shapes can't pass through others. Returns how far through the move they first touch.
+    pub fn sweep(&self, other: &Self) -> Option<f32> {
+        let start = self.step_offset();
+        let motion = -start;
+        let target = other.bounds();
+        let time = match self.kind {
+            ShapeKind::Circle => self.circle().offset(start).sweep_rect(&motion, &target),
+            _ => self.bounds().offset(start).sweep_rect(&motion, &target),
+        }?;
+        let exact = matches!(other.kind, ShapeKind::Rect)
+            && matches!(self.kind, ShapeKind::Rect | ShapeKind::Circle);
+        if exact {
+            return Some(time);
         }
+
+        // Other shapes only touched the bounds, so step through the rest of the move
+        // in strides shorter than the shape to find where they really meet
+        let distance = motion.length() * (1.0 - time);
+        let steps = (distance / self.half_width().max(1.0)).ceil().max(1.0) as u32;
+        let mut moved = self.clone();
+        (0..=steps)
+            .map(|i| time + (1.0 - time) * i as f32 / steps as f32)
+            .find(|&t| {
+                moved.position = self.previous_position + motion * t;
+                moved.collides_with(other)
+            })
+    }
+}
+
+/// What a `Shape` looks like and collides as, filling a box `size` wide
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum ShapeKind {
+    #[default]
+    Rect,
+    Circle,
+    /// Convex, with points in order around the center in fractions of `size`
+    Polygon(Vec<Position>),
+    /// A vertical beam `length` times as long as it is wide
+    Capsule {
+        length: f32,
+    },
+}
+
+impl ShapeKind {
+    /// A triangle pointing down the screen
+    pub fn triangle() -> Self {
+        ShapeKind::Polygon(vec![
+            Position { x: -0.5, y: -0.5 },
+            Position { x: 0.5, y: -0.5 },
+            Position { x: 0.0, y: 0.5 },
+        ])
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FirePattern {
+    /// A single bullet fired straight at the player
+    Aimed,
+    /// `count` bullets fanned across `arc` radians, centered on the player
+    Spread { count: u32, arc: f32 },
+    /// `count` bullets evenly spaced around the shooter
+    Ring { count: u32 },
+}
+
+impl FirePattern {
+    /// Returns the unit direction of every bullet in one volley
+    pub fn directions(&self, origin: &Position, target: &Position) -> Vec<Vec2> {
+        let aim = (target.y - origin.y).atan2(target.x - origin.x);
+
+        let angles: Vec<f32> = match self {
+            FirePattern::Aimed => vec![aim],
+            FirePattern::Spread { count, arc } => {
+                if *count <= 1 {
+                    vec![aim]
+                } else {
+                    let step = arc / (*count - 1) as f32;
+                    (0..*count)
+                        .map(|i| aim - arc / 2.0 + step * i as f32)
+                        .collect()
+                }
+            }
+            FirePattern::Ring { count } => {
+                let step = TAU / (*count).max(1) as f32;
+                (0..*count).map(|i| aim + step * i as f32).collect()
+            }
+        };
+
+        angles.into_iter().map(Vec2::from_angle).collect()
+    }
+}
+
+/// Describes an enemy to spawn, any field left empty is randomized
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EnemySpec {
+    /// Horizontal position as a fraction of the screen width
+    pub x: Option<f32>,
+    pub size: Option<f32>,
+    pub speed: Option<f32>,
+    pub fire_pattern: Option<FirePattern>,
+    #[serde(default)]
+    pub movement: Movement,
+    #[serde(default)]
+    pub kind: EnemyKind,
+}
+
+fn default_health() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Ship {
     pub shape: Shape,
-    pub last_fired: u64,
+    /// Simulation time of the last shot, in seconds
+    pub last_fired: f32,
     pub is_dead: bool,
+    #[serde(default)]
+    pub fire_pattern: Option<FirePattern>,
+    #[serde(default)]
+    pub respawn_time: f32,
+    #[serde(default)]
+    pub invulnerable_time: f32,
+    /// Older snapshots predate movement patterns, everything then went straight down
+    #[serde(default)]
+    pub movement: Movement,
+    #[serde(default)]
+    pub path: PathState,
+    #[serde(default)]
+    pub kind: EnemyKind,
+    /// Hits left before the ship is destroyed
+    #[serde(default = "default_health")]
+    pub health: u32,
 }
 
 impl Ship {
     pub fn new_player(screen: &Screen) -> Self {
+        let position = Position {
+            x: screen.width() / 2.0,
+            y: screen.height() / 2.0,
+        };
         Self {
             shape: Shape {
-                position: Position {
-                    x: screen.width() / 2.0,
-                    y: screen.height() / 2.0,
-                },
-                speed: MOVEMENT_SPEED,
+                previous_position: position,
+                position,
+                velocity: Vec2::ZERO,
                 size: 32.0,
                 color: YELLOW,
-                is_circle: true,
+                kind: ShapeKind::Circle,
             },
             is_dead: false,
-            last_fired: 0,
+            last_fired: 0.0,
+            fire_pattern: None,
+            respawn_time: 0.0,
+            invulnerable_time: 0.0,
+            movement: Movement::Straight,
+            path: PathState::default(),
+            kind: EnemyKind::default(),
+            health: 1,
         }
     }
 
-    pub fn new_enemy(screen: &Screen) -> Self {
-        let mut rng = thread_rng();
-        let size = rng.gen_range(16.0..64.0);
-        let speed = rng.gen_range(50.0..150.0);
-        let position = Position {
-            x: rng.gen_range((size / 2.0)..(screen.width() - size / 2.0)),
-            y: -size,
+    pub fn new_enemy(screen: &Screen, time: f32, spec: &EnemySpec, rng: &mut impl Rng) -> Self {
+        let kind = spec.kind;
+        let size = spec
+            .size
+            .unwrap_or_else(|| rng.gen_range(kind.size_range()));
+        let speed = spec
+            .speed
+            .unwrap_or_else(|| rng.gen_range(kind.speed_range()));
+        let x = match spec.x {
+            Some(x) => (x * screen.width()).clamp(size / 2.0, screen.width() - size / 2.0),
+            None => rng.gen_range((size / 2.0)..(screen.width() - size / 2.0)),
         };
-        let color = [WHITE, RED, AQUA, BLUE, YELLOW].choose(&mut rng).unwrap();
+        let position = Position { x, y: -size };
+        let color = kind.color().unwrap_or_else(|| {
+            [WHITE, RED, AQUA, BLUE, YELLOW]
+                .choose(rng)
+                .unwrap()
+                .clone()
+        });
         Ship {
+            path: PathState::new(position),
             shape: Shape {
+                previous_position: position,
                 position,
-                speed,
+                velocity: Vec2::DOWN * speed,
                 size,
-                color: color.clone(),
-                is_circle: false,
+                color,
+                kind: kind.shape(),
             },
             is_dead: false,
-            last_fired: 0,
+            // Enemies wait a full cooldown after spawning before their first volley
+            last_fired: time,
+            fire_pattern: spec.fire_pattern.clone(),
+            respawn_time: 0.0,
+            invulnerable_time: 0.0,
+            movement: spec.movement.clone(),
+            kind,
+            health: kind.health(),
         }
     }
 
-    const FIRE_RATE: u64 = 30;
-    pub fn can_shoot(&self, update_frame: u64) -> bool {
-        (update_frame - self.last_fired) > Self::FIRE_RATE
+    /// The `count` smaller enemies left behind when `parent` breaks apart,
+    /// spread side by side and diving at the player
+    pub fn new_fragments(
+        parent: &Ship,
+        kind: EnemyKind,
+        count: u32,
+        time: f32,
+        rng: &mut impl Rng,
+    ) -> Vec<Self> {
+        (0..count)
+            .map(|i| {
+                let offset = (i as f32 - (count - 1) as f32 / 2.0) * parent.shape.size;
+                let position = parent.shape.position + Vec2::new(offset, 0.0);
+                Ship {
+                    path: PathState::new(position),
+                    shape: Shape {
+                        previous_position: position,
+                        position,
+                        velocity: Vec2::DOWN * rng.gen_range(kind.speed_range()),
+                        size: rng.gen_range(kind.size_range()),
+                        color: kind.color().unwrap_or(WHITE),
+                        kind: kind.shape(),
+                    },
+                    is_dead: false,
+                    last_fired: time,
+                    fire_pattern: None,
+                    respawn_time: 0.0,
+                    invulnerable_time: 0.0,
+                    movement: Movement::Dive {
+                        delay: 0.3,
+                        speed_multiplier: 1.5,
+                    },
+                    kind,
+                    health: kind.health(),
+                }
+            })
+            .collect()
+    }
+
+    pub fn is_respawning(&self) -> bool {
+        self.respawn_time > 0.0
+    }
+
+    pub fn is_invulnerable(&self) -> bool {
+        self.is_respawning() || self.invulnerable_time > 0.0
+    }
+
+    /// `fire_rate` is the cooldown between shots, in seconds
+    pub fn can_shoot(&self, time: f32, fire_rate: f32) -> bool {
+        (time - self.last_fired) > fire_rate
+    }
+
+    pub fn can_enemy_shoot(&self, time: f32, fire_rate: f32) -> bool {
+        self.fire_pattern.is_some()
+            && self.shape.position.y > 0.0
+            && (time - self.last_fired) > fire_rate
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "SavedBullet")]
 pub struct Bullet {
     pub shape: Shape,
     pub collided: bool,
+    pub piercing: bool,
+}
+
+/// A `Bullet` as saved, which before velocities kept its direction apart from its speed
+#[derive(Deserialize)]
+struct SavedBullet {
+    shape: Shape,
+    collided: bool,
+    #[serde(default)]
+    piercing: bool,
+    #[serde(default)]
+    direction: Option<Vec2>,
+}
+
+impl From<SavedBullet> for Bullet {
+    fn from(saved: SavedBullet) -> Self {
+        let mut shape = saved.shape;
+        if let Some(direction) = saved.direction {
+            shape.velocity = direction * shape.speed();
+        }
+        Bullet {
+            shape,
+            collided: saved.collided,
+            piercing: saved.piercing,
+        }
+    }
 }
 
 impl Bullet {
-    pub fn new(shooter: &Ship) -> Bullet {
+    pub fn new(shooter: &Ship, direction: Vec2) -> Bullet {
         Bullet {
             shape: Shape {
-                position: shooter.shape.position.clone(),
-                speed: shooter.shape.speed * 2.0,
+                position: shooter.shape.position,
+                previous_position: shooter.shape.position,
+                velocity: direction * PLAYER_BULLET_SPEED,
                 size: 5.0,
                 color: RED,
-                is_circle: true,
+                kind: ShapeKind::Circle,
             },
             collided: false,
+            piercing: false,
+        }
+    }
+
+    pub fn new_enemy(shooter: &Shape, direction: Vec2) -> Bullet {
+        Bullet {
+            shape: Shape {
+                position: shooter.position,
+                previous_position: shooter.position,
+                velocity: direction * ENEMY_BULLET_SPEED,
+                size: 8.0,
+                color: ORANGE,
+                kind: ShapeKind::Circle,
+            },
+            collided: false,
+            piercing: false,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum GamePhase {
     MainMenu,
+    Leaderboard,
     Playing,
     Paused,
+    Options,
+    Bindings,
+    /// The player made the high score table and is typing their initials
+    EnterInitials,
     GameOver,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameState {
     pub update_frame: u64,
+    /// Seconds of simulation run, which stops while paused
+    #[serde(default)]
+    pub time: f32,
     pub phase: GamePhase,
 
+    /// All gameplay randomness comes from `rng`, so a seed and
+    /// the input that followed are enough to replay a game exactly
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default = "default_rng")]
+    pub rng: Pcg32,
+    /// Digits typed on the main menu to choose the next game's seed
+    #[serde(default)]
+    pub seed_entry: String,
+
     pub player: Ship,
     pub enemies: Vec<Ship>,
     pub bullets: Vec<Bullet>,
+    #[serde(default)]
+    pub enemy_bullets: Vec<Bullet>,
+    #[serde(default)]
+    pub powerups: Vec<PowerUp>,
+    #[serde(default)]
+    pub active_powerups: ActivePowerUps,
+    #[serde(default)]
+    pub boss: Option<Boss>,
+    #[serde(default)]
+    pub boss_schedule: BossSchedule,
+    #[serde(skip)]
+    pub particles: ParticleSystem,
+    #[serde(skip)]
+    pub shake: ScreenShake,
+    #[serde(skip)]
+    pub widgets: WidgetState,
+    /// The action on the key bindings screen waiting for a key to be pressed
+    #[serde(skip)]
+    pub rebinding: Option<Action>,
     pub score: HighScore,
+    #[serde(default)]
+    pub combo: Combo,
+    #[serde(skip)]
+    pub popups: Vec<ScorePopup>,
+    #[serde(default)]
+    pub initials_entry: String,
+    /// Where this game's score landed in the high score table, if it made it
+    #[serde(default)]
+    pub placed_rank: Option<usize>,
+    #[serde(default = "default_lives")]
+    pub lives: u32,
+    #[serde(default)]
+    pub waves: WaveProgress,
+    #[serde(default)]
+    pub tuning: Tuning,
+    #[serde(default)]
+    pub settings: Settings,
+    #[serde(default)]
+    pub stats: RunStats,
 
     pub starfield_direction_modifier: f32,
 }
 
+fn default_lives() -> u32 {
+    STARTING_LIVES
+}
+
+// Snapshots from before seeds were saved restore with a seed of zero, and this matches it
+fn default_rng() -> Pcg32 {
+    Pcg32::seed_from_u64(0)
+}
+
 impl GameState {
-    pub fn new(screen: &Screen) -> Self {
+    /// `score` and `settings` are passed in rather than loaded so tests and headless runs never touch disk
+    pub fn new(screen: &Screen, score: HighScore, settings: Settings) -> Self {
+        Self::new_with_seed(screen, thread_rng().gen(), score, settings)
+    }
+
+    pub fn new_with_seed(screen: &Screen, seed: u64, score: HighScore, settings: Settings) -> Self {
         Self {
             update_frame: 0,
+            time: 0.0,
             phase: GamePhase::MainMenu,
+            seed,
+            rng: Pcg32::seed_from_u64(seed),
+            seed_entry: String::new(),
             player: Ship::new_player(screen),
             enemies: vec![],
             bullets: vec![],
-            score: HighScore::load(),
+            enemy_bullets: vec![],
+            powerups: vec![],
+            active_powerups: ActivePowerUps::default(),
+            boss: None,
+            boss_schedule: BossSchedule::default(),
+            particles: ParticleSystem::default(),
+            shake: ScreenShake::default(),
+            widgets: WidgetState::default(),
+            rebinding: None,
+            score,
+            combo: Combo::default(),
+            popups: vec![],
+            initials_entry: String::new(),
+            placed_rank: None,
+            lives: STARTING_LIVES,
+            waves: WaveProgress::default(),
+            tuning: settings.difficulty.tuning(),
+            settings,
+            stats: RunStats::default(),
             starfield_direction_modifier: 0.0,
         }
     }
 
-    pub fn add_enemy(&mut self, screen: &Screen) {
-        if thread_rng().gen_range(0..99) > 95 {
-            self.enemies.push(Ship::new_enemy(screen));
+    /// A new game that keeps the high scores and settings already loaded,
+    /// with a random seed if none is given
+    pub fn restart(&self, screen: &Screen, seed: Option<u64>) -> Self {
+        let score = self.score.new_game();
+        let settings = self.settings.clone();
+        match seed {
+            Some(seed) => Self::new_with_seed(screen, seed, score, settings),
+            None => Self::new(screen, score, settings),
         }
     }
 
-    pub fn check_player_hit(&mut self) {
+    pub fn spawn_enemies(&mut self, screen: &Screen, frame_time: f32) {
         if matches!(self.phase, GamePhase::Playing) {
-            if self
-                .enemies
-                .iter()
-                .any(|s| s.shape.collides_with(&self.player.shape))
-            {
+            // Waves hold off until the boss is beaten
+            if self.boss.is_none() {
+                self.run_waves(screen, frame_time);
+            }
+        } else {
+            self.add_enemy(screen);
+        }
+    }
+
+    // Menus and the game over screen get a steady trickle of harmless enemies
+    fn add_enemy(&mut self, screen: &Screen) {
+        if self.rng.gen_range(0..99) > 95 {
+            self.enemies.push(Ship::new_enemy(
+                screen,
+                self.time,
+                &EnemySpec::default(),
+                &mut self.rng,
+            ));
+        }
+    }
+
+    fn run_waves(&mut self, screen: &Screen, frame_time: f32) {
+        let spawns = self
+            .waves
+            .update(frame_time, self.tuning.spawn_rate, self.enemies.is_empty());
+        for spec in spawns {
+            self.enemies
+                .push(Ship::new_enemy(screen, self.time, &spec, &mut self.rng));
+        }
+    }
+
+    pub fn update_boss(&mut self, screen: &Screen, frame_time: f32) {
+        self.boss_schedule.play_time += frame_time;
+
+        match &mut self.boss {
+            Some(boss) => {
+                let bullets = boss.update(screen, frame_time, &self.player.shape.position);
+                self.enemy_bullets.extend(bullets);
+            }
+            None => {
+                if self.boss_schedule.is_due(self.score.current_score()) {
+                    let encounter = self.boss_schedule.start_encounter();
+                    self.boss = Some(Boss::new(screen, encounter));
+                }
+            }
+        }
+    }
+
+    pub fn check_boss_hits(&mut self) {
+        let Some(boss) = &mut self.boss else {
+            return;
+        };
+
+        for bullet in &mut self.bullets {
+            if !bullet.collided && !boss.is_dead() && bullet.shape.sweep(&boss.shape).is_some() {
+                // Piercing rounds still stop on a boss, or they'd hit it every frame
+                bullet.collided = true;
+                boss.damage(1);
+                self.particles.burst(&IMPACT, &bullet.shape.position);
+            }
+        }
+
+        if boss.is_dead() {
+            self.particles.burst_with_color(
+                &PLAYER_DEATH,
+                &boss.shape.position,
+                boss.shape.color.clone(),
+            );
+            let multiplier = self.combo.register_kill();
+            self.score.add(boss.reward() * multiplier as u64);
+            self.popups.push(ScorePopup::new(
+                boss.shape.position,
+                boss.reward(),
+                multiplier,
+            ));
+            self.shake.add_trauma(BOSS_DEATH_TRAUMA);
+            self.boss_schedule.defeated(self.score.current_score());
+            self.boss = None;
+        }
+    }
+
+    pub fn update_player_timers(&mut self, frame_time: f32) {
+        self.active_powerups.update(frame_time);
+        self.combo.update(frame_time);
+
+        let player = &mut self.player;
+        if player.is_respawning() {
+            player.respawn_time = (player.respawn_time - frame_time).max(0.0);
+            if !player.is_respawning() {
+                player.invulnerable_time = INVULNERABLE_TIME;
+            }
+        } else {
+            player.invulnerable_time = (player.invulnerable_time - frame_time).max(0.0);
+        }
+    }
+
+    pub fn check_player_hit(&mut self, screen: &Screen) {
+        if !matches!(self.phase, GamePhase::Playing) || self.player.is_invulnerable() {
+            return;
+        }
+
+        let player = &self.player.shape;
+        let cause = if self.enemies.iter().any(|s| s.shape.collides_with(player)) {
+            Some(DeathCause::Enemy)
+        } else if self
+            .enemy_bullets
+            .iter()
+            .any(|b| b.shape.sweep(player).is_some())
+        {
+            Some(DeathCause::EnemyBullet)
+        } else if self
+            .boss
+            .as_ref()
+            .is_some_and(|b| b.shape.collides_with(player))
+        {
+            Some(DeathCause::Boss)
+        } else {
+            None
+        };
+
+        if let Some(cause) = cause {
+            self.combo.reset();
+            if self.active_powerups.consume(PowerUpKind::Shield) {
+                self.player.invulnerable_time = SHIELD_BREAK_TIME;
+                self.shake.add_trauma(SHIELD_BREAK_TRAUMA);
+                return;
+            }
+
+            self.shake.add_trauma(PLAYER_HIT_TRAUMA);
+            self.particles
+                .burst(&PLAYER_DEATH, &self.player.shape.position);
+            self.lives = self.lives.saturating_sub(1);
+            if self.lives == 0 {
                 self.player.is_dead = true;
-                self.phase = GamePhase::GameOver;
-                self.score.save();
+                self.stats.cause_of_death = Some(cause);
+                self.phase = if self.score.qualifies() {
+                    GamePhase::EnterInitials
+                } else {
+                    GamePhase::GameOver
+                };
+            } else {
+                self.respawn_player(screen);
+            }
+        }
+    }
+
+    pub fn collect_powerups(&mut self) {
+        for power_up in &mut self.powerups {
+            if !self.player.is_respawning() && power_up.shape.collides_with(&self.player.shape) {
+                power_up.collected = true;
+                self.active_powerups.activate(power_up.kind);
             }
         }
     }
+
+    fn respawn_player(&mut self, screen: &Screen) {
+        self.player = Ship::new_player(screen);
+        self.player.respawn_time = RESPAWN_TIME;
+        self.active_powerups.clear();
+        // Give the new ship a clean slate rather than respawning into a volley
+        self.enemy_bullets.clear();
+    }
+
+    fn shapes_mut(&mut self) -> impl Iterator<Item = &mut Shape> {
+        std::iter::once(&mut self.player.shape)
+            .chain(self.enemies.iter_mut().map(|e| &mut e.shape))
+            .chain(self.bullets.iter_mut().map(|b| &mut b.shape))
+            .chain(self.enemy_bullets.iter_mut().map(|b| &mut b.shape))
+            .chain(self.powerups.iter_mut().map(|p| &mut p.shape))
+            .chain(self.boss.iter_mut().map(|b| &mut b.shape))
+    }
+
+    /// Called before each simulation step so rendering knows where things came from
+    pub fn store_previous_positions(&mut self) {
+        for shape in self.shapes_mut() {
+            shape.store_previous_position();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::infrastructure::RecordingScreen;
+
+    // A snapshot from an older build, before `state_fields` and `ship_fields` existed
+    fn restore_without(state_fields: &[&str], ship_fields: &[&str]) -> GameState {
+        let screen = RecordingScreen::new(800.0, 600.0);
+        let mut state =
+            GameState::new_with_seed(&screen, 1, HighScore::in_memory(), Settings::in_memory());
+        let enemy = Ship::new_enemy(&screen, 0.0, &EnemySpec::default(), &mut state.rng);
+        state.enemies.push(enemy);
+
+        let mut saved = serde_json::to_value(&state).unwrap();
+        for field in state_fields {
+            saved.as_object_mut().unwrap().remove(*field);
+        }
+        for field in ship_fields {
+            for ship in ["/player", "/enemies/0"] {
+                saved
+                    .pointer_mut(ship)
+                    .unwrap()
+                    .as_object_mut()
+                    .unwrap()
+                    .remove(*field);
+            }
+        }
+        serde_json::from_value(saved).unwrap()
+    }
+
+    #[test]
+    fn older_snapshots_restore() {
+        let state = restore_without(&["enemy_bullets"], &["fire_pattern"]);
+        assert!(state.enemy_bullets.is_empty());
+        assert!(state.enemies[0].fire_pattern.is_none());
+
+        let state = restore_without(&["waves"], &[]);
+        assert_eq!(1, state.waves.wave_number);
+
+        let state = restore_without(&["lives"], &["respawn_time", "invulnerable_time"]);
+        assert_eq!(STARTING_LIVES, state.lives);
+        assert_eq!(0.0, state.player.invulnerable_time);
+
+        let state = restore_without(&["powerups", "active_powerups"], &[]);
+        assert!(state.powerups.is_empty());
+        let mut bullet = serde_json::to_value(Bullet::new(&state.player, Vec2::UP)).unwrap();
+        bullet.as_object_mut().unwrap().remove("piercing");
+        assert!(!serde_json::from_value::<Bullet>(bullet).unwrap().piercing);
+
+        let state = restore_without(&["boss", "boss_schedule"], &[]);
+        assert!(state.boss.is_none());
+
+        let state = restore_without(&[], &["movement", "path"]);
+        assert!(matches!(state.enemies[0].movement, Movement::Straight));
+
+        let mut state = restore_without(&["seed", "rng", "seed_entry"], &[]);
+        assert_eq!(0, state.seed);
+        assert_eq!(Pcg32::seed_from_u64(0).gen::<u64>(), state.rng.gen::<u64>());
+
+        let state = restore_without(&["time"], &[]);
+        assert_eq!(0.0, state.time);
+    }
+
+    #[test]
+    fn first_release_snapshot_restores() {
+        let shape = r#"{
+            "position": {"x": 10.0, "y": 20.0},
+            "speed": 120.0,
+            "size": 5.0,
+            "color": {"r": 1.0, "g": 0.0, "b": 0.0, "a": 1.0},
+            "is_circle": false
+        }"#;
+        let ship = format!(r#"{{"shape": {shape}, "last_fired": 30, "is_dead": false}}"#);
+        let saved = format!(
+            r#"{{
+                "update_frame": 40,
+                "phase": "Playing",
+                "player": {ship},
+                "enemies": [{ship}],
+                "bullets": [{{"shape": {shape}, "collided": false}}],
+                "score": {{"score": 50, "high_score": 200}},
+                "starfield_direction_modifier": 0.0
+            }}"#
+        );
+        let state: GameState = serde_json::from_str(&saved).unwrap();
+        assert_eq!(1, state.enemies.len());
+        assert_eq!(STARTING_LIVES, state.lives);
+    }
+
+    #[test]
+    fn older_saved_shapes_restore() {
+        let shape = r#"{
+            "position": {"x": 10.0, "y": 20.0},
+            "speed": 120.0,
+            "size": 5.0,
+            "color": {"r": 1.0, "g": 0.0, "b": 0.0, "a": 1.0}
+        }"#;
+        let enemy: Shape = serde_json::from_str(shape).unwrap();
+        assert_eq!(Vec2::DOWN * 120.0, enemy.velocity);
+        assert_eq!(enemy.position, enemy.previous_position);
+
+        let bullet = format!(
+            r#"{{"shape": {shape}, "direction": {{"x": 0.0, "y": -1.0}}, "collided": false, "piercing": false}}"#
+        );
+        let bullet: Bullet = serde_json::from_str(&bullet).unwrap();
+        assert_eq!(Vec2::UP * 120.0, bullet.shape.velocity);
+
+        let saved = serde_json::to_string(&bullet).unwrap();
+        let restored: Bullet = serde_json::from_str(&saved).unwrap();
+        assert_eq!(bullet.shape.velocity, restored.shape.velocity);
+    }
 }
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// What the player ran into to lose their last life
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeathCause {
+    Enemy,
+    EnemyBullet,
+    Boss,
+}
+
+/// Running totals for the current game
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunStats {
+    pub frames_survived: u64,
+    pub enemies_killed: u64,
+    pub cause_of_death: Option<DeathCause>,
+}
+
+/// How a game went, for batch balance runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub seed: u64,
+    pub score: u64,
+    pub wave: u32,
+    pub frames_survived: u64,
+    pub enemies_killed: u64,
+    pub cause_of_death: Option<DeathCause>,
+}
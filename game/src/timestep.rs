@@ -0,0 +1,142 @@
+use crate::{
+    caffeinated_gorilla::space::types::ClickInfo,
+    exports::caffeinated_gorilla::space::game_api::{GamepadInfo, KeyboardInfo, MouseInfo},
+};
+
+/// Simulation steps per second unless configured otherwise
+pub const DEFAULT_SIMULATION_RATE: f32 = 60.0;
+
+// A long hitch (a breakpoint, dragging the window) would otherwise
+// queue up hundreds of steps at once
+const MAX_FRAME_TIME: f32 = 0.25;
+
+/// Runs the simulation in fixed size steps regardless of frame rate,
+/// carrying leftover time over to the next frame
+#[derive(Debug)]
+pub struct FixedTimestep {
+    step: f32,
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    pub fn new(rate: f32) -> Self {
+        Self {
+            step: 1.0 / rate,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Length of a single step, in seconds
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
+    /// Adds a frame's worth of time, returning how many steps to run
+    pub fn advance(&mut self, frame_time: f32) -> u32 {
+        self.accumulator += frame_time.clamp(0.0, MAX_FRAME_TIME);
+        let steps = (self.accumulator / self.step) as u32;
+        self.accumulator -= steps as f32 * self.step;
+        steps
+    }
+
+    /// How far between the last step and the next the current frame is, from 0 to 1
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.step).clamp(0.0, 1.0)
+    }
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self::new(DEFAULT_SIMULATION_RATE)
+    }
+}
+
+/// Input gathered between simulation steps.
+///
+/// Frames that run no steps still need their presses and releases seen,
+/// so they are held until the next step takes them.
+#[derive(Debug)]
+pub struct PendingInput {
+    mouse: MouseInfo,
+    key: KeyboardInfo,
+    gamepad: GamepadInfo,
+}
+
+impl Default for PendingInput {
+    fn default() -> Self {
+        let click = ClickInfo {
+            pressed: false,
+            released: false,
+            down: false,
+        };
+        Self {
+            mouse: MouseInfo {
+                position: (0.0, 0.0).into(),
+                left: click,
+                right: click,
+                middle: click,
+            },
+            key: KeyboardInfo {
+                pressed: vec![],
+                released: vec![],
+                down: vec![],
+            },
+            gamepad: GamepadInfo::default(),
+        }
+    }
+}
+
+fn merge_click(pending: &mut ClickInfo, click: ClickInfo) {
+    pending.pressed |= click.pressed;
+    pending.released |= click.released;
+    pending.down = click.down;
+}
+
+fn merge_keys<T: PartialEq>(pending: &mut Vec<T>, keys: Vec<T>) {
+    for key in keys {
+        if !pending.contains(&key) {
+            pending.push(key);
+        }
+    }
+}
+
+impl PendingInput {
+    pub fn add(&mut self, mouse: MouseInfo, key: KeyboardInfo, gamepad: GamepadInfo) {
+        self.mouse.position = mouse.position;
+        merge_click(&mut self.mouse.left, mouse.left);
+        merge_click(&mut self.mouse.right, mouse.right);
+        merge_click(&mut self.mouse.middle, mouse.middle);
+
+        merge_keys(&mut self.key.pressed, key.pressed);
+        merge_keys(&mut self.key.released, key.released);
+        self.key.down = key.down;
+
+        merge_keys(&mut self.gamepad.pressed, gamepad.pressed);
+        merge_keys(&mut self.gamepad.released, gamepad.released);
+        self.gamepad = GamepadInfo {
+            pressed: std::mem::take(&mut self.gamepad.pressed),
+            released: std::mem::take(&mut self.gamepad.released),
+            ..gamepad
+        };
+    }
+
+    /// Input for the next step. Presses and releases are only reported once.
+    pub fn take(&mut self) -> (MouseInfo, KeyboardInfo, GamepadInfo) {
+        let input = (self.mouse, self.key.clone(), self.gamepad.clone());
+
+        for click in [
+            &mut self.mouse.left,
+            &mut self.mouse.right,
+            &mut self.mouse.middle,
+        ] {
+            click.pressed = false;
+            click.released = false;
+        }
+        self.key.pressed.clear();
+        self.key.released.clear();
+        self.gamepad.pressed.clear();
+        self.gamepad.released.clear();
+
+        input
+    }
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+use crate::state::MOVEMENT_SPEED;
+
+/// Gameplay numbers that can be changed per game, so balance can be
+/// explored without rebuilding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tuning {
+    /// Player speed, in pixels per second
+    pub movement_speed: f32,
+    /// Seconds between player shots
+    pub fire_rate: f32,
+    /// Seconds between player shots with the rapid fire power-up
+    pub rapid_fire_rate: f32,
+    /// Seconds between enemy volleys
+    pub enemy_fire_rate: f32,
+    /// Multiplier on how quickly waves spawn their enemies
+    pub spawn_rate: f32,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            movement_speed: MOVEMENT_SPEED,
+            fire_rate: 0.5,
+            rapid_fire_rate: 0.17,
+            enemy_fire_rate: 1.5,
+            spawn_rate: 1.0,
+        }
+    }
+}
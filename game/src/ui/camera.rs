@@ -0,0 +1,170 @@
+use crate::{
+    caffeinated_gorilla::space::types::{GameColor, Position, Size},
+    infrastructure::Screen,
+    math::Vec2,
+};
+
+/// Scales and then moves points, a uniform scale so circles stay round
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub offset: Vec2,
+    pub scale: f32,
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        offset: Vec2::ZERO,
+        scale: 1.0,
+    };
+
+    pub fn translate(offset: Vec2) -> Self {
+        Transform { offset, scale: 1.0 }
+    }
+
+    pub fn scale(scale: f32) -> Self {
+        Transform {
+            offset: Vec2::ZERO,
+            scale,
+        }
+    }
+
+    /// Scales by `scale` around `center` rather than the top left of the screen
+    pub fn scale_around(center: Vec2, scale: f32) -> Self {
+        Transform::translate(center)
+            .then(Transform::scale(scale))
+            .then(Transform::translate(-center))
+    }
+
+    /// The transform that applies `inner` first, then this one
+    pub fn then(self, inner: Transform) -> Self {
+        Transform {
+            offset: inner.offset * self.scale + self.offset,
+            scale: inner.scale * self.scale,
+        }
+    }
+
+    pub fn apply(&self, point: Vec2) -> Vec2 {
+        point * self.scale + self.offset
+    }
+}
+
+/// Draws to the screen through a stack of transforms. Each pushed transform
+/// applies inside the ones below it, and only to what's drawn before it's popped.
+pub struct Camera<'a> {
+    screen: &'a Screen,
+    stack: Vec<Transform>,
+}
+
+impl<'a> Camera<'a> {
+    pub fn new(screen: &'a Screen) -> Self {
+        Camera {
+            screen,
+            stack: vec![],
+        }
+    }
+
+    pub fn screen(&self) -> &Screen {
+        self.screen
+    }
+
+    /// Everything the stack adds up to
+    pub fn transform(&self) -> Transform {
+        self.stack.last().copied().unwrap_or(Transform::IDENTITY)
+    }
+
+    pub fn push(&mut self, transform: Transform) {
+        self.stack.push(self.transform().then(transform));
+    }
+
+    pub fn pop(&mut self) {
+        self.stack.pop();
+    }
+
+    /// Draws whatever `draw` does with `transform` pushed
+    pub fn with_transform(&mut self, transform: Transform, draw: impl FnOnce(&mut Self)) {
+        self.push(transform);
+        draw(self);
+        self.pop();
+    }
+
+    fn point(&self, position: Position) -> Position {
+        self.transform().apply(position.into()).into()
+    }
+
+    fn length(&self, length: f32) -> f32 {
+        length * self.transform().scale
+    }
+
+    fn size(&self, size: Size) -> Size {
+        Size {
+            width: self.length(size.width),
+            height: self.length(size.height),
+        }
+    }
+
+    /// The screen's width in the units being drawn in, so things
+    /// placed from the right edge stay on screen when scaled
+    pub fn width(&self) -> f32 {
+        self.screen.width() / self.transform().scale
+    }
+
+    pub fn height(&self) -> f32 {
+        self.screen.height() / self.transform().scale
+    }
+
+    pub fn draw_text(&self, text: &str, position: Position, size: u16, color: GameColor) {
+        let size = self.length(size as f32).round() as u16;
+        self.screen
+            .draw_text(text, self.point(position), size, color);
+    }
+
+    pub fn draw_line(&self, first: Position, second: Position, thickness: f32, color: GameColor) {
+        self.screen.draw_line(
+            self.point(first),
+            self.point(second),
+            self.length(thickness),
+            color,
+        );
+    }
+
+    pub fn draw_rectangle(&self, position: Position, size: Size, color: GameColor) {
+        self.screen
+            .draw_rectangle(self.point(position), self.size(size), color);
+    }
+
+    pub fn draw_circle(&self, position: Position, radius: f32, color: GameColor) {
+        self.screen
+            .draw_circle(self.point(position), self.length(radius), color);
+    }
+
+    pub fn draw_triangle(
+        &self,
+        first: Position,
+        second: Position,
+        third: Position,
+        color: GameColor,
+    ) {
+        self.screen.draw_triangle(
+            self.point(first),
+            self.point(second),
+            self.point(third),
+            color,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn transforms_compose_inside_out() {
+        let zoom = Transform::scale_around(Vec2::new(100.0, 100.0), 2.0);
+        assert_eq!(Vec2::new(100.0, 100.0), zoom.apply(Vec2::new(100.0, 100.0)));
+        assert_eq!(Vec2::new(120.0, 80.0), zoom.apply(Vec2::new(110.0, 90.0)));
+
+        let shaken = Transform::translate(Vec2::new(5.0, 0.0)).then(zoom);
+        assert_eq!(Vec2::new(125.0, 80.0), shaken.apply(Vec2::new(110.0, 90.0)));
+        assert_eq!(shaken, shaken.then(Transform::IDENTITY));
+    }
+}
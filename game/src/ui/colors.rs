@@ -8,6 +8,17 @@ pub struct Color {
     pub a: f32,
 }
 
+impl Color {
+    pub fn lerp(&self, other: &Color, t: f32) -> Color {
+        Color {
+            r: self.r + (other.r - self.r) * t,
+            g: self.g + (other.g - self.g) * t,
+            b: self.b + (other.b - self.b) * t,
+            a: self.a + (other.a - self.a) * t,
+        }
+    }
+}
+
 impl From<Color> for crate::caffeinated_gorilla::space::types::GameColor {
     fn from(value: Color) -> Self {
         crate::caffeinated_gorilla::space::types::GameColor {
@@ -53,3 +64,39 @@ pub const YELLOW: Color = Color {
     b: 0.0,
     a: 1.0,
 };
+
+pub const ORANGE: Color = Color {
+    r: 1.0,
+    g: 0.6,
+    b: 0.0,
+    a: 1.0,
+};
+
+pub const GREEN: Color = Color {
+    r: 0.2,
+    g: 0.9,
+    b: 0.3,
+    a: 1.0,
+};
+
+pub const PURPLE: Color = Color {
+    r: 0.7,
+    g: 0.3,
+    b: 1.0,
+    a: 1.0,
+};
+
+pub const GRAY: Color = Color {
+    r: 0.6,
+    g: 0.6,
+    b: 0.65,
+    a: 1.0,
+};
+
+/// Behind HUD bars and menu widgets
+pub const PANEL: Color = Color {
+    r: 0.2,
+    g: 0.2,
+    b: 0.2,
+    a: 0.8,
+};
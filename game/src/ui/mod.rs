@@ -1,12 +1,18 @@
-use crate::{
-    caffeinated_gorilla::space::types::{GameColor, Position, Size},
-    infrastructure::Screen,
-};
+use crate::caffeinated_gorilla::space::types::{GameColor, Position, Size};
 
 #[allow(dead_code)]
 mod colors;
 pub use colors::*;
 
+mod camera;
+pub use camera::{Camera, Transform};
+
+mod shake;
+pub use shake::ScreenShake;
+
+mod widgets;
+pub use widgets::{Ui, WidgetState};
+
 impl From<(f32, f32)> for Position {
     fn from(value: (f32, f32)) -> Self {
         Position {
@@ -49,14 +55,14 @@ pub trait ScreenExt {
     fn centered_text(&self, text: &str, position: (f32, f32), size: TextSize, color: GameColor);
 }
 
-impl ScreenExt for Screen {
+impl ScreenExt for Camera<'_> {
     fn text(&self, text: &str, position: (f32, f32), size: TextSize, color: GameColor) {
         self.draw_text(text, (position.0, position.1).into(), size.into(), color);
     }
 
     fn centered_text(&self, text: &str, position: (f32, f32), size: TextSize, color: GameColor) {
         let size = size.into();
-        let dimensions = self.measure_text(text, size);
+        let dimensions = self.screen().measure_text(text, size);
         let text_position = Position {
             x: position.0 - (dimensions.width / 2.0),
             y: position.1,
@@ -0,0 +1,45 @@
+use crate::math::Vec2;
+
+use super::Transform;
+
+// Trauma lost per second, so a full shake settles in a little under a second
+const TRAUMA_DECAY: f32 = 1.2;
+// Pixels the view moves at full trauma
+const MAX_OFFSET: f32 = 12.0;
+// How much further the view zooms in at full trauma
+const MAX_ZOOM: f32 = 0.03;
+const FREQUENCY: f32 = 40.0;
+
+/// Screen shake driven by trauma, from 0 to 1, that hits add to and time wears off.
+/// The shake grows with the square of trauma so small hits stay subtle.
+#[derive(Debug, Clone, Default)]
+pub struct ScreenShake {
+    trauma: f32,
+}
+
+impl ScreenShake {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.0);
+    }
+
+    pub fn update(&mut self, frame_time: f32) {
+        self.trauma = (self.trauma - TRAUMA_DECAY * frame_time).max(0.0);
+    }
+
+    /// The camera transform for the world at `time`, zooming around `center`
+    pub fn transform(&self, time: f32, center: Vec2) -> Transform {
+        if self.trauma <= 0.0 {
+            return Transform::IDENTITY;
+        }
+
+        let shake = self.trauma * self.trauma;
+        // Out of step sine waves stand in for noise, so shaking doesn't use up
+        // the game's random numbers and change what happens next
+        let direction = Vec2::new(
+            (time * FREQUENCY).sin() + (time * FREQUENCY * 2.3).sin() * 0.5,
+            (time * FREQUENCY * 1.3 + 1.0).sin() + (time * FREQUENCY * 2.9).sin() * 0.5,
+        );
+        Transform::translate(direction * (MAX_OFFSET * shake / 1.5))
+            .then(Transform::scale_around(center, 1.0 + MAX_ZOOM * shake))
+    }
+}
@@ -0,0 +1,426 @@
+use crate::{
+    bindings::{Action, Bindings},
+    caffeinated_gorilla::space::types::Key,
+    exports::caffeinated_gorilla::space::game_api::{GamepadInfo, KeyboardInfo, MouseInfo},
+    math::{Rect, Vec2},
+};
+
+use super::{Camera, Color, ScreenExt, TextSize, BLUE, GRAY, PANEL, WHITE, YELLOW};
+
+pub const WIDGET_WIDTH: f32 = 260.0;
+pub const WIDGET_HEIGHT: f32 = 32.0;
+const WIDGET_SPACING: f32 = 8.0;
+
+/// What widgets remember between frames. Everything else is
+/// described again each frame by the code laying them out.
+#[derive(Debug, Clone, Default)]
+pub struct WidgetState {
+    /// Index of the widget with keyboard focus, in the order they were laid out
+    focus: usize,
+    /// Widgets laid out last time, so focus knows where to wrap
+    count: usize,
+    /// Where the mouse was last step, focus only follows it once it moves
+    mouse: Option<Vec2>,
+    /// The slider the mouse is dragging
+    dragging: Option<usize>,
+}
+
+impl WidgetState {
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+}
+
+enum Pass<'a, 'b> {
+    Input {
+        mouse: Vec2,
+        mouse_moved: bool,
+        click: bool,
+        held: bool,
+        activate: bool,
+        left: bool,
+        right: bool,
+    },
+    Draw(&'a Camera<'b>),
+}
+
+/// Immediate mode widgets. A screen lays out its widgets in one function which
+/// runs twice a frame: once from `step` with the input, where widgets report
+/// what was clicked or changed, and once from `draw` with a camera to draw them.
+/// Widgets stack down the screen from the cursor set by `column`.
+pub struct Ui<'a, 'b> {
+    state: &'a mut WidgetState,
+    pass: Pass<'a, 'b>,
+    next: usize,
+    cursor: Vec2,
+}
+
+impl<'a, 'b> Ui<'a, 'b> {
+    /// Focus moves with the movement actions or Tab and Confirm activates
+    pub fn input(
+        state: &'a mut WidgetState,
+        mouse: &MouseInfo,
+        key: &KeyboardInfo,
+        gamepad: &GamepadInfo,
+        bindings: &Bindings,
+    ) -> Self {
+        let position: Vec2 = mouse.position.into();
+        let mouse_moved = state.mouse.is_some_and(|last| last != position);
+        state.mouse = Some(position);
+        if !mouse.left.down {
+            state.dragging = None;
+        }
+
+        let pressed = |action| bindings.pressed(action, key, gamepad);
+        if state.count > 0 {
+            if pressed(Action::MoveDown) || key.pressed.contains(&Key::Tab) {
+                state.focus = (state.focus + 1) % state.count;
+            }
+            if pressed(Action::MoveUp) {
+                state.focus = (state.focus + state.count - 1) % state.count;
+            }
+        }
+
+        Ui {
+            state,
+            pass: Pass::Input {
+                mouse: position,
+                mouse_moved,
+                click: mouse.left.pressed,
+                held: mouse.left.down,
+                activate: pressed(Action::Confirm),
+                left: pressed(Action::MoveLeft),
+                right: pressed(Action::MoveRight),
+            },
+            next: 0,
+            cursor: Vec2::ZERO,
+        }
+    }
+
+    pub fn draw(state: &'a mut WidgetState, camera: &'a Camera<'b>) -> Self {
+        Ui {
+            state,
+            pass: Pass::Draw(camera),
+            next: 0,
+            cursor: Vec2::ZERO,
+        }
+    }
+
+    /// Widgets after this are centered on `x`, starting from `y`
+    pub fn column(&mut self, x: f32, y: f32) {
+        self.cursor = Vec2::new(x, y);
+    }
+
+    fn next_row(&mut self) -> Rect {
+        let bounds = Rect::new(
+            self.cursor.x - WIDGET_WIDTH / 2.0,
+            self.cursor.y,
+            WIDGET_WIDTH,
+            WIDGET_HEIGHT,
+        );
+        self.cursor.y += WIDGET_HEIGHT + WIDGET_SPACING;
+        bounds
+    }
+
+    /// Lays out the next widget, taking focus if the mouse moved onto or clicked it
+    fn next_widget(&mut self) -> (usize, Rect) {
+        let id = self.next;
+        self.next += 1;
+        let bounds = self.next_row();
+
+        if let Pass::Input {
+            mouse,
+            mouse_moved,
+            click,
+            ..
+        } = self.pass
+        {
+            if (mouse_moved || click) && bounds.contains(mouse) {
+                self.state.focus = id;
+            }
+        }
+        (id, bounds)
+    }
+
+    fn is_focused(&self, id: usize) -> bool {
+        self.state.focus == id
+    }
+
+    fn clicked(&self, bounds: &Rect) -> bool {
+        match self.pass {
+            Pass::Input { mouse, click, .. } => click && bounds.contains(mouse),
+            Pass::Draw(_) => false,
+        }
+    }
+
+    fn activated(&self, id: usize) -> bool {
+        matches!(self.pass, Pass::Input { activate: true, .. }) && self.is_focused(id)
+    }
+
+    /// -1 or 1 when left or right is pressed while focused
+    fn nudge(&self, id: usize) -> i32 {
+        match self.pass {
+            Pass::Input { left, right, .. } if self.is_focused(id) => right as i32 - left as i32,
+            _ => 0,
+        }
+    }
+
+    fn draw_widget(&self, id: usize, bounds: &Rect, label: &str, text_color: Color) {
+        if let Pass::Draw(camera) = self.pass {
+            let background = if self.is_focused(id) { BLUE } else { PANEL };
+            camera.draw_rectangle(
+                bounds.point().into(),
+                bounds.size().into(),
+                background.into(),
+            );
+            draw_label(camera, bounds, label, text_color);
+        }
+    }
+
+    /// Text taking up a widget's row that can't be focused
+    pub fn label(&mut self, text: &str) {
+        let bounds = self.next_row();
+        if let Pass::Draw(camera) = self.pass {
+            draw_label(camera, &bounds, text, GRAY);
+        }
+    }
+
+    /// True the step it's clicked, or activated with Confirm while focused
+    pub fn button(&mut self, label: &str) -> bool {
+        let (id, bounds) = self.next_widget();
+        self.draw_widget(id, &bounds, label, WHITE);
+        self.clicked(&bounds) || self.activated(id)
+    }
+
+    /// Flips `value` when clicked, activated or nudged with the arrow keys.
+    /// True if it changed.
+    pub fn toggle(&mut self, label: &str, value: &mut bool) -> bool {
+        let (id, bounds) = self.next_widget();
+        let changed = self.clicked(&bounds) || self.activated(id) || self.nudge(id) != 0;
+        if changed {
+            *value = !*value;
+        }
+        let state = if *value { "On" } else { "Off" };
+        self.draw_widget(id, &bounds, &format!("{label}: {state}"), WHITE);
+        changed
+    }
+
+    /// Sets `value` between `min` and `max` by dragging across it or in
+    /// `step` increments with the arrow keys. True if it changed.
+    pub fn slider(&mut self, label: &str, value: &mut f32, min: f32, max: f32, step: f32) -> bool {
+        let (id, bounds) = self.next_widget();
+        let before = *value;
+
+        if self.clicked(&bounds) {
+            self.state.dragging = Some(id);
+        }
+        if let Pass::Input {
+            mouse, held: true, ..
+        } = self.pass
+        {
+            if self.state.dragging == Some(id) {
+                let t = ((mouse.x - bounds.x) / bounds.w).clamp(0.0, 1.0);
+                // Snapped to `step` so dragging lands on the same values the keys do
+                *value = min + ((max - min) * t / step).round() * step;
+            }
+        }
+        *value = (*value + self.nudge(id) as f32 * step).clamp(min, max);
+
+        if let Pass::Draw(camera) = self.pass {
+            let background = if self.is_focused(id) { BLUE } else { PANEL };
+            camera.draw_rectangle(
+                bounds.point().into(),
+                bounds.size().into(),
+                background.into(),
+            );
+            let mut fill = WHITE;
+            fill.a = 0.3;
+            let t = (*value - min) / (max - min);
+            camera.draw_rectangle(
+                bounds.point().into(),
+                (bounds.w * t, bounds.h).into(),
+                fill.into(),
+            );
+            draw_label(camera, &bounds, label, WHITE);
+        }
+        *value != before
+    }
+
+    /// A column of items with `selected` highlighted. Each item takes focus
+    /// on its own and clicking or activating one selects it. True if it changed.
+    pub fn list(&mut self, items: &[&str], selected: &mut usize) -> bool {
+        let before = *selected;
+        for (index, item) in items.iter().enumerate() {
+            let (id, bounds) = self.next_widget();
+            if self.clicked(&bounds) || self.activated(id) {
+                *selected = index;
+            }
+            let color = if index == *selected { YELLOW } else { WHITE };
+            self.draw_widget(id, &bounds, item, color);
+        }
+        *selected != before
+    }
+}
+
+impl Drop for Ui<'_, '_> {
+    fn drop(&mut self) {
+        // Keep focus on a widget that still exists when the layout shrinks
+        self.state.count = self.next;
+        self.state.focus = self.state.focus.min(self.next.saturating_sub(1));
+    }
+}
+
+fn draw_label(camera: &Camera, bounds: &Rect, label: &str, color: Color) {
+    let size: u16 = TextSize::Standard.into();
+    let height = camera.screen().measure_text(label, size).offset_y;
+    let center = bounds.center();
+    camera.centered_text(
+        label,
+        (center.x, center.y + height / 2.0),
+        TextSize::Standard,
+        color.into(),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::caffeinated_gorilla::space::types::{ClickInfo, Key};
+
+    fn input(position: (f32, f32), click: bool, keys: Vec<Key>) -> (MouseInfo, KeyboardInfo) {
+        let released = ClickInfo {
+            pressed: false,
+            released: false,
+            down: false,
+        };
+        let mouse = MouseInfo {
+            position: position.into(),
+            left: ClickInfo {
+                pressed: click,
+                released: false,
+                down: click,
+            },
+            right: released,
+            middle: released,
+        };
+        let key = KeyboardInfo {
+            pressed: keys,
+            released: vec![],
+            down: vec![],
+        };
+        (mouse, key)
+    }
+
+    // A menu like the game's: two buttons, a toggle and a slider from (100, 0)
+    fn menu(
+        state: &mut WidgetState,
+        input: (MouseInfo, KeyboardInfo),
+        enabled: &mut bool,
+        volume: &mut f32,
+    ) -> Vec<bool> {
+        let mut ui = Ui::input(
+            state,
+            &input.0,
+            &input.1,
+            &GamepadInfo::default(),
+            &Bindings::default(),
+        );
+        ui.column(200.0, 0.0);
+        vec![
+            ui.button("First"),
+            ui.button("Second"),
+            ui.toggle("Enabled", enabled),
+            ui.slider("Volume", volume, 0.0, 1.0, 0.25),
+        ]
+    }
+
+    #[test]
+    fn widgets_respond_to_mouse_and_keys() {
+        let mut state = WidgetState::default();
+        let (mut enabled, mut volume) = (false, 0.5);
+
+        // Nothing is pressed on the first step, the layout is only measured
+        let results = menu(
+            &mut state,
+            input((0.0, 0.0), false, vec![]),
+            &mut enabled,
+            &mut volume,
+        );
+        assert_eq!(vec![false; 4], results);
+
+        let results = menu(
+            &mut state,
+            input((0.0, 0.0), false, vec![Key::Enter]),
+            &mut enabled,
+            &mut volume,
+        );
+        assert_eq!(vec![true, false, false, false], results);
+
+        // Focus wraps around from the top
+        menu(
+            &mut state,
+            input((0.0, 0.0), false, vec![Key::Up]),
+            &mut enabled,
+            &mut volume,
+        );
+        assert_eq!(3, state.focus());
+        menu(
+            &mut state,
+            input((0.0, 0.0), false, vec![Key::Left]),
+            &mut enabled,
+            &mut volume,
+        );
+        assert_eq!(0.25, volume);
+
+        // Clicking the second button, 40 pixels down, focuses it too
+        let results = menu(
+            &mut state,
+            input((150.0, 50.0), true, vec![]),
+            &mut enabled,
+            &mut volume,
+        );
+        assert_eq!(vec![false, true, false, false], results);
+        assert_eq!(1, state.focus());
+
+        menu(
+            &mut state,
+            input((150.0, 90.0), true, vec![]),
+            &mut enabled,
+            &mut volume,
+        );
+        assert!(enabled);
+
+        // Dragging to the far right of the slider fills it
+        menu(
+            &mut state,
+            input((75.0, 130.0), true, vec![]),
+            &mut enabled,
+            &mut volume,
+        );
+        assert_eq!(0.0, volume);
+        menu(
+            &mut state,
+            input((400.0, 130.0), true, vec![]),
+            &mut enabled,
+            &mut volume,
+        );
+        assert_eq!(1.0, volume);
+    }
+
+    #[test]
+    fn list_selects_clicked_item() {
+        let mut state = WidgetState::default();
+        let mut selected = 0;
+        let (mouse, key) = input((200.0, 45.0), true, vec![]);
+        let mut ui = Ui::input(
+            &mut state,
+            &mouse,
+            &key,
+            &GamepadInfo::default(),
+            &Bindings::default(),
+        );
+        ui.column(200.0, 0.0);
+        assert!(ui.list(&["Easy", "Normal", "Hard"], &mut selected));
+        assert_eq!(1, selected);
+    }
+}
@@ -0,0 +1,220 @@
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::state::EnemySpec;
+
+const LEVEL_DATA: &str = include_str!("levels.json");
+
+// How long the "Wave N" banner stays up, in seconds
+const BANNER_TIME: f32 = 2.5;
+
+#[derive(Debug, Deserialize)]
+pub struct Level {
+    pub name: String,
+    pub waves: Vec<Wave>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Wave {
+    pub groups: Vec<SpawnGroup>,
+}
+
+/// A run of identical enemies within a wave
+#[derive(Debug, Deserialize)]
+pub struct SpawnGroup {
+    /// Seconds after the wave starts before the first enemy appears
+    #[serde(default)]
+    pub delay: f32,
+    /// Seconds between each enemy in the group
+    #[serde(default)]
+    pub interval: f32,
+    pub count: u32,
+    /// Added to the enemy's `x` for each enemy after the first,
+    /// letting a group spawn as a line across the screen
+    #[serde(default)]
+    pub x_step: f32,
+    #[serde(default)]
+    pub enemy: EnemySpec,
+}
+
+pub fn levels() -> &'static [Level] {
+    static LEVELS: OnceLock<Vec<Level>> = OnceLock::new();
+    LEVELS.get_or_init(|| serde_json::from_str(LEVEL_DATA).expect("Unable to parse level data"))
+}
+
+pub struct Banner {
+    pub title: String,
+    pub subtitle: Option<String>,
+}
+
+/// Where the player is in the level data. Only indexes and timers are
+/// stored so saves stay valid as long as the level data is compatible.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "SavedWaveProgress")]
+pub struct WaveProgress {
+    pub level: usize,
+    pub wave: usize,
+    /// Count of waves started this game, used for the banner
+    pub wave_number: u32,
+    /// Seconds since the current wave started
+    pub elapsed: f32,
+    /// Enemies spawned so far by each group in the current wave
+    pub spawned: Vec<u32>,
+    /// Seconds remaining to show the banner
+    pub banner_time: f32,
+}
+
+impl Default for WaveProgress {
+    fn default() -> Self {
+        let mut progress = Self {
+            level: 0,
+            wave: 0,
+            wave_number: 0,
+            elapsed: 0.0,
+            spawned: vec![],
+            banner_time: 0.0,
+        };
+        progress.start_wave();
+        progress
+    }
+}
+
+/// A `WaveProgress` as saved, which may point past the end of the current level data
+#[derive(Deserialize)]
+struct SavedWaveProgress {
+    level: usize,
+    wave: usize,
+    wave_number: u32,
+    elapsed: f32,
+    spawned: Vec<u32>,
+    banner_time: f32,
+}
+
+impl From<SavedWaveProgress> for WaveProgress {
+    fn from(saved: SavedWaveProgress) -> Self {
+        let mut progress = Self {
+            level: saved.level,
+            wave: saved.wave,
+            wave_number: saved.wave_number,
+            elapsed: saved.elapsed,
+            spawned: saved.spawned,
+            banner_time: saved.banner_time,
+        };
+        // Start over on the nearest level that still exists, keeping the wave count
+        let levels = levels();
+        if progress.level >= levels.len() || progress.wave >= levels[progress.level].waves.len() {
+            progress.level = progress.level.min(levels.len() - 1);
+            progress.wave = 0;
+            progress.wave_number = progress.wave_number.saturating_sub(1);
+            progress.start_wave();
+        }
+        progress
+    }
+}
+
+impl WaveProgress {
+    fn current_wave(&self) -> &'static Wave {
+        &levels()[self.level].waves[self.wave]
+    }
+
+    fn start_wave(&mut self) {
+        self.wave_number += 1;
+        self.elapsed = 0.0;
+        self.spawned = vec![0; self.current_wave().groups.len()];
+        self.banner_time = BANNER_TIME;
+    }
+
+    fn advance(&mut self) {
+        let levels = levels();
+        self.wave += 1;
+        if self.wave >= levels[self.level].waves.len() {
+            self.wave = 0;
+            // Once every level has been cleared, keep replaying the last one
+            self.level = (self.level + 1).min(levels.len() - 1);
+        }
+        self.start_wave();
+    }
+
+    fn is_fully_spawned(&self) -> bool {
+        self.current_wave()
+            .groups
+            .iter()
+            .zip(&self.spawned)
+            .all(|(group, spawned)| *spawned >= group.count)
+    }
+
+    /// Steps the wave timers, returning any enemies due to spawn this frame.
+    /// Moves on to the next wave once everything has spawned and
+    /// no enemies remain on screen. `spawn_rate` speeds up or slows down spawning.
+    pub fn update(
+        &mut self,
+        frame_time: f32,
+        spawn_rate: f32,
+        enemies_cleared: bool,
+    ) -> Vec<EnemySpec> {
+        self.elapsed += frame_time * spawn_rate;
+        self.banner_time = (self.banner_time - frame_time).max(0.0);
+
+        if self.is_fully_spawned() && enemies_cleared {
+            self.advance();
+            return vec![];
+        }
+
+        let mut spawns = vec![];
+        let wave = self.current_wave();
+        for (group, spawned) in wave.groups.iter().zip(self.spawned.iter_mut()) {
+            while *spawned < group.count
+                && self.elapsed >= group.delay + group.interval * *spawned as f32
+            {
+                let mut enemy = group.enemy.clone();
+                if let Some(x) = enemy.x {
+                    enemy.x = Some(x + group.x_step * *spawned as f32);
+                }
+                spawns.push(enemy);
+                *spawned += 1;
+            }
+        }
+        spawns
+    }
+
+    pub fn banner(&self) -> Option<Banner> {
+        if self.banner_time <= 0.0 {
+            return None;
+        }
+
+        Some(Banner {
+            title: format!("Wave {}", self.wave_number),
+            subtitle: if self.wave == 0 {
+                Some(levels()[self.level].name.clone())
+            } else {
+                None
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn level_data_parses() {
+        let levels = levels();
+        assert!(!levels.is_empty());
+        assert!(levels.iter().all(|level| !level.waves.is_empty()));
+    }
+
+    #[test]
+    fn missing_waves_restart_last_level() {
+        let mut saved = serde_json::to_value(WaveProgress::default()).unwrap();
+        saved["level"] = 99.into();
+        saved["wave"] = 99.into();
+        saved["wave_number"] = 12.into();
+        let progress: WaveProgress = serde_json::from_value(saved).unwrap();
+        assert_eq!(levels().len() - 1, progress.level);
+        assert_eq!(0, progress.wave);
+        assert_eq!(12, progress.wave_number);
+        assert_eq!(progress.current_wave().groups.len(), progress.spawned.len());
+    }
+}
@@ -9,9 +9,14 @@ use wasmtime::component::{Component, Linker, Resource, ResourceAny};
 use wasmtime::{Config, Engine, Store};
 use wasmtime_wasi::{DirPerms, FilePerms, ResourceTable, WasiCtx, WasiCtxBuilder, WasiView};
 
-use exports::caffeinated_gorilla::space::game_api::{GuestGameInstance, KeyboardInfo, MouseInfo};
+use exports::caffeinated_gorilla::space::game_api::{
+    GamepadInfo, GuestGameInstance, KeyboardInfo, MouseInfo,
+};
 
 use super::wasm_path;
+
+// Matches the variable the game reads in score.rs
+const DATA_DIR_ENV: &str = "SPACE_SHOOTER_DATA_DIR";
 pub use crate::GameScreen;
 
 wasmtime::component::bindgen!({
@@ -119,6 +124,20 @@ impl caffeinated_gorilla::space::host_api::HostGameScreen for MyState {
         Ok(())
     }
 
+    fn draw_triangle(
+        &mut self,
+        screen: Resource<GameScreen>,
+        first: Position,
+        second: Position,
+        third: Position,
+        color: GameColor,
+    ) -> wasmtime::Result<()> {
+        debug_assert!(!screen.owned());
+        let screen = self.table.get(&screen)?;
+        screen.draw_triangle(first, second, third, color);
+        Ok(())
+    }
+
     fn width(&mut self, screen: Resource<GameScreen>) -> wasmtime::Result<f32> {
         debug_assert!(!screen.owned());
         Ok(screen_width())
@@ -201,6 +220,12 @@ impl WebAssemblyContext {
         let mut wasi = WasiCtxBuilder::new();
         wasi.inherit_stdio();
         wasi.preopened_dir(".", ".", DirPerms::all(), FilePerms::all())?;
+        // Give the game access to a data directory outside the working directory, if set
+        if let Ok(data_dir) = std::env::var(DATA_DIR_ENV) {
+            std::fs::create_dir_all(&data_dir)?;
+            wasi.preopened_dir(&data_dir, &data_dir, DirPerms::all(), FilePerms::all())?;
+            wasi.env(DATA_DIR_ENV, &data_dir);
+        }
 
         let store = Store::new(
             &engine,
@@ -266,7 +291,9 @@ impl GameInstance<'_> {
         &self,
         mouse: MouseInfo,
         key: KeyboardInfo,
+        gamepad: GamepadInfo,
         screen: GameScreen,
+        frame_time: f32,
     ) -> Result<()> {
         let mut context = self.context.lock().unwrap();
         let screen = context.store.data_mut().convert_to_resource(screen)?;
@@ -276,8 +303,9 @@ impl GameInstance<'_> {
             self.instance,
             mouse,
             &key,
+            &gamepad,
             screen,
-            get_frame_time(),
+            frame_time,
         )
     }
 
@@ -289,6 +317,13 @@ impl GameInstance<'_> {
             .call_render_frame(&mut context.store, self.instance, screen)
     }
 
+    pub fn set_simulation_rate(&self, rate: f32) -> Result<()> {
+        let mut context = self.context.lock().unwrap();
+
+        self.instance_type
+            .call_set_simulation_rate(&mut context.store, self.instance, rate)
+    }
+
     pub fn save(&self) -> Result<Vec<u8>> {
         let mut context = self.context.lock().unwrap();
 
@@ -306,8 +341,15 @@ impl GameInstance<'_> {
 
 #[async_trait::async_trait]
 impl crate::RunnableGameInstance for GameInstance<'_> {
-    fn update_frame(&self, mouse: MouseInfo, key: KeyboardInfo, screen: GameScreen) {
-        if let Err(e) = GameInstance::update_frame(self, mouse, key, screen) {
+    fn update_frame(
+        &self,
+        mouse: MouseInfo,
+        key: KeyboardInfo,
+        gamepad: GamepadInfo,
+        screen: GameScreen,
+        frame_time: f32,
+    ) {
+        if let Err(e) = GameInstance::update_frame(self, mouse, key, gamepad, screen, frame_time) {
             println!("Error in updating frame: {e:?}");
         }
     }
@@ -317,7 +359,20 @@ impl crate::RunnableGameInstance for GameInstance<'_> {
             println!("Error in rendering frame: {e:?}");
         }
     }
+
+    fn set_simulation_rate(&self, rate: f32) {
+        if let Err(e) = GameInstance::set_simulation_rate(self, rate) {
+            println!("Error in setting simulation rate: {e:?}");
+        }
+    }
+
     fn save(&self) -> String {
         String::from_utf8(GameInstance::save(&self).unwrap_or_default()).unwrap_or_default()
     }
+
+    fn restore(&self, data: String) {
+        if let Err(e) = GameInstance::load(self, data.into_bytes()) {
+            println!("Error in restoring state: {e:?}");
+        }
+    }
 }
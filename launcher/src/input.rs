@@ -1,15 +1,173 @@
 use macroquad::prelude::*;
 
 #[cfg(feature = "hotreload")]
-use crate::hotreload::binding::caffeinated_gorilla::space::types::{
-    ClickInfo, Key, KeyboardInfo, MouseInfo, Position,
+pub use crate::hotreload::binding::caffeinated_gorilla::space::types::{
+    ClickInfo, GamepadButton, GamepadInfo, Key, KeyboardInfo, MouseInfo, Position,
 };
 
 #[cfg(not(feature = "hotreload"))]
 pub use game::caffeinated_gorilla::space::types::{
-    ClickInfo, Key, KeyboardInfo, MouseInfo, Position,
+    ClickInfo, GamepadButton, GamepadInfo, Key, KeyboardInfo, MouseInfo, Position,
 };
 
+/// Every `Key` in the order declared in interface.wit, so a key's index
+/// is the same for both the direct and hotreload bindings
+pub const ALL_KEYS: [Key; 121] = [
+    Key::Space,
+    Key::Apostrophe,
+    Key::Comma,
+    Key::Minus,
+    Key::Period,
+    Key::Slash,
+    Key::KeyZero,
+    Key::KeyOne,
+    Key::KeyTwo,
+    Key::KeyThree,
+    Key::KeyFour,
+    Key::KeyFive,
+    Key::KeySix,
+    Key::KeySeven,
+    Key::KeyEight,
+    Key::KeyNine,
+    Key::Semicolon,
+    Key::Equal,
+    Key::A,
+    Key::B,
+    Key::C,
+    Key::D,
+    Key::E,
+    Key::F,
+    Key::G,
+    Key::H,
+    Key::I,
+    Key::J,
+    Key::K,
+    Key::L,
+    Key::M,
+    Key::N,
+    Key::O,
+    Key::P,
+    Key::Q,
+    Key::R,
+    Key::S,
+    Key::T,
+    Key::U,
+    Key::V,
+    Key::W,
+    Key::X,
+    Key::Y,
+    Key::Z,
+    Key::LeftBracket,
+    Key::Backslash,
+    Key::RightBracket,
+    Key::GraveAccent,
+    Key::WorldOne,
+    Key::WorldTwo,
+    Key::Escape,
+    Key::Enter,
+    Key::Tab,
+    Key::Backspace,
+    Key::Insert,
+    Key::Delete,
+    Key::Right,
+    Key::Left,
+    Key::Down,
+    Key::Up,
+    Key::PageUp,
+    Key::PageDown,
+    Key::Home,
+    Key::End,
+    Key::CapsLock,
+    Key::ScrollLock,
+    Key::NumLock,
+    Key::PrintScreen,
+    Key::Pause,
+    Key::F1,
+    Key::F2,
+    Key::F3,
+    Key::F4,
+    Key::F5,
+    Key::F6,
+    Key::F7,
+    Key::F8,
+    Key::F9,
+    Key::F10,
+    Key::F11,
+    Key::F12,
+    Key::F13,
+    Key::F14,
+    Key::F15,
+    Key::F16,
+    Key::F17,
+    Key::F18,
+    Key::F19,
+    Key::F20,
+    Key::F21,
+    Key::F22,
+    Key::F23,
+    Key::F24,
+    Key::F25,
+    Key::Kp0,
+    Key::Kp1,
+    Key::Kp2,
+    Key::Kp3,
+    Key::Kp4,
+    Key::Kp5,
+    Key::Kp6,
+    Key::Kp7,
+    Key::Kp8,
+    Key::Kp9,
+    Key::KpDecimal,
+    Key::KpDivide,
+    Key::KpMultiply,
+    Key::KpSubtract,
+    Key::KpAdd,
+    Key::KpEnter,
+    Key::KpEqual,
+    Key::LeftShift,
+    Key::LeftControl,
+    Key::LeftAlt,
+    Key::LeftSuper,
+    Key::RightShift,
+    Key::RightControl,
+    Key::RightAlt,
+    Key::RightSuper,
+    Key::Menu,
+    Key::Unknown,
+];
+
+pub fn key_index(key: &Key) -> u8 {
+    ALL_KEYS
+        .iter()
+        .position(|k| k == key)
+        .expect("Key missing from ALL_KEYS") as u8
+}
+
+/// Every `GamepadButton` in the order declared in interface.wit, for the same reason as `ALL_KEYS`
+pub const ALL_BUTTONS: [GamepadButton; 14] = [
+    GamepadButton::South,
+    GamepadButton::East,
+    GamepadButton::West,
+    GamepadButton::North,
+    GamepadButton::LeftBumper,
+    GamepadButton::RightBumper,
+    GamepadButton::Select,
+    GamepadButton::Start,
+    GamepadButton::LeftStick,
+    GamepadButton::RightStick,
+    GamepadButton::DpadUp,
+    GamepadButton::DpadDown,
+    GamepadButton::DpadLeft,
+    GamepadButton::DpadRight,
+];
+
+pub fn button_index(button: &GamepadButton) -> u8 {
+    ALL_BUTTONS
+        .iter()
+        .position(|b| b == button)
+        .expect("Button missing from ALL_BUTTONS") as u8
+}
+
 pub fn get_key_info() -> KeyboardInfo {
     KeyboardInfo {
         pressed: get_keys_pressed().into_iter().map(map_key).collect(),
@@ -168,3 +326,119 @@ pub fn get_mouse_state() -> MouseInfo {
         },
     }
 }
+
+pub fn disconnected_gamepad() -> GamepadInfo {
+    GamepadInfo {
+        connected: false,
+        left_stick: Position { x: 0.0, y: 0.0 },
+        right_stick: Position { x: 0.0, y: 0.0 },
+        left_trigger: 0.0,
+        right_trigger: 0.0,
+        pressed: vec![],
+        released: vec![],
+        down: vec![],
+    }
+}
+
+/// Reads the first connected gamepad. Presses and releases are found by
+/// comparing against the buttons down last frame, like the keyboard.
+pub struct Gamepads {
+    #[cfg(feature = "gamepad")]
+    gilrs: Option<gilrs::Gilrs>,
+    down: Vec<GamepadButton>,
+}
+
+// Only derivable without the `gamepad` feature, when there's no gilrs to create
+#[cfg_attr(not(feature = "gamepad"), allow(clippy::derivable_impls))]
+impl Default for Gamepads {
+    fn default() -> Self {
+        Self {
+            // Without a usable backend the game still runs, just keyboard only
+            #[cfg(feature = "gamepad")]
+            gilrs: gilrs::Gilrs::new()
+                .inspect_err(|e| println!("Gamepads unavailable: {e:?}"))
+                .ok(),
+            down: vec![],
+        }
+    }
+}
+
+impl Gamepads {
+    pub fn get_gamepad_state(&mut self) -> GamepadInfo {
+        let Some(mut gamepad) = self.read() else {
+            self.down.clear();
+            return disconnected_gamepad();
+        };
+        gamepad.pressed = gamepad
+            .down
+            .iter()
+            .filter(|b| !self.down.contains(b))
+            .cloned()
+            .collect();
+        gamepad.released = self
+            .down
+            .iter()
+            .filter(|b| !gamepad.down.contains(b))
+            .cloned()
+            .collect();
+        self.down = gamepad.down.clone();
+        gamepad
+    }
+
+    #[cfg(feature = "gamepad")]
+    fn read(&mut self) -> Option<GamepadInfo> {
+        use gilrs::{Axis, Button};
+
+        let gilrs = self.gilrs.as_mut()?;
+        // gilrs only updates its gamepad state as events are taken
+        while gilrs.next_event().is_some() {}
+        let (_, gamepad) = gilrs.gamepads().next()?;
+
+        // gilrs has up as positive, the game has y increasing down the screen
+        let stick = |x, y| Position {
+            x: gamepad.value(x),
+            y: -gamepad.value(y),
+        };
+        let trigger = |button| gamepad.button_data(button).map_or(0.0, |data| data.value());
+        Some(GamepadInfo {
+            connected: true,
+            left_stick: stick(Axis::LeftStickX, Axis::LeftStickY),
+            right_stick: stick(Axis::RightStickX, Axis::RightStickY),
+            left_trigger: trigger(Button::LeftTrigger2),
+            right_trigger: trigger(Button::RightTrigger2),
+            pressed: vec![],
+            released: vec![],
+            down: ALL_BUTTONS
+                .into_iter()
+                .filter(|b| gamepad.is_pressed(map_button(b)))
+                .collect(),
+        })
+    }
+
+    #[cfg(not(feature = "gamepad"))]
+    fn read(&mut self) -> Option<GamepadInfo> {
+        None
+    }
+}
+
+#[cfg(feature = "gamepad")]
+fn map_button(button: &GamepadButton) -> gilrs::Button {
+    use gilrs::Button;
+
+    match button {
+        GamepadButton::South => Button::South,
+        GamepadButton::East => Button::East,
+        GamepadButton::West => Button::West,
+        GamepadButton::North => Button::North,
+        GamepadButton::LeftBumper => Button::LeftTrigger,
+        GamepadButton::RightBumper => Button::RightTrigger,
+        GamepadButton::Select => Button::Select,
+        GamepadButton::Start => Button::Start,
+        GamepadButton::LeftStick => Button::LeftThumb,
+        GamepadButton::RightStick => Button::RightThumb,
+        GamepadButton::DpadUp => Button::DPadUp,
+        GamepadButton::DpadDown => Button::DPadDown,
+        GamepadButton::DpadLeft => Button::DPadLeft,
+        GamepadButton::DpadRight => Button::DPadRight,
+    }
+}
@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, path::Path};
 
 use anyhow::Result;
 use async_trait::async_trait;
@@ -13,6 +13,9 @@ use crate::hotreload::binding::caffeinated_gorilla::space::types::{GameColor, Ke
 mod input;
 use input::*;
 
+mod replay;
+use replay::{InputPlayback, InputRecorder, InputSource};
+
 mod screen;
 pub use screen::GameScreen;
 
@@ -26,13 +29,13 @@ mod hotreload;
 
 #[cfg(feature = "hotreload")]
 use crate::hotreload::binding::{
-    caffeinated_gorilla::space::types::{KeyboardInfo, MouseInfo},
+    caffeinated_gorilla::space::types::{GamepadInfo, KeyboardInfo, MouseInfo},
     WebAssemblyContext, WebAssemblyInstance,
 };
 
 #[cfg(not(feature = "hotreload"))]
 pub use game::{
-    exports::caffeinated_gorilla::space::game_api::{KeyboardInfo, MouseInfo},
+    exports::caffeinated_gorilla::space::game_api::{GamepadInfo, KeyboardInfo, MouseInfo},
     Game,
 };
 
@@ -40,25 +43,49 @@ use texture_cache::TextureCache;
 
 #[async_trait]
 pub trait RunnableGameInstance: Send + Sync {
-    fn update_frame(&self, mouse: MouseInfo, key: KeyboardInfo, screen: GameScreen);
+    fn update_frame(
+        &self,
+        mouse: MouseInfo,
+        key: KeyboardInfo,
+        gamepad: GamepadInfo,
+        screen: GameScreen,
+        frame_time: f32,
+    );
     fn render_frame(&self, screen: GameScreen);
+    fn set_simulation_rate(&self, rate: f32);
     fn save(&self) -> String;
+    fn restore(&self, data: String);
 }
 
 #[cfg(not(feature = "hotreload"))]
 #[async_trait]
 impl RunnableGameInstance for Game {
-    fn update_frame(&self, mouse: MouseInfo, key: KeyboardInfo, screen: GameScreen) {
-        Game::update_frame(self, mouse, key, &screen, get_frame_time())
+    fn update_frame(
+        &self,
+        mouse: MouseInfo,
+        key: KeyboardInfo,
+        gamepad: GamepadInfo,
+        screen: GameScreen,
+        frame_time: f32,
+    ) {
+        Game::update_frame(self, mouse, key, gamepad, &screen, frame_time)
     }
 
     fn render_frame(&self, screen: GameScreen) {
         Game::render_frame(self, &screen)
     }
 
+    fn set_simulation_rate(&self, rate: f32) {
+        Game::set_simulation_rate(self, rate)
+    }
+
     fn save(&self) -> String {
         String::from_utf8(Game::save(&self)).unwrap_or_default()
     }
+
+    fn restore(&self, data: String) {
+        Game::restore(self, data.into_bytes())
+    }
 }
 
 #[derive(Debug, Default)]
@@ -72,13 +99,48 @@ impl DebugState {
     }
 }
 
+// The value following `flag` on the command line
+fn flag_value(flag: &str) -> Option<String> {
+    let mut args = std::env::args().skip_while(|arg| arg != flag);
+    args.next()?;
+    args.next()
+}
+
+// Reads `--record <file>` or `--replay <file>` from the command line
+fn input_source<R: RunnableGameInstance>(instance: &R) -> Result<InputSource> {
+    if let Some(path) = flag_value("--replay") {
+        let playback = InputPlayback::load(Path::new(&path))?;
+        instance.restore(playback.initial_state().to_string());
+        Ok(InputSource::Replay(playback))
+    } else if let Some(path) = flag_value("--record") {
+        let recorder = InputRecorder::create(Path::new(&path), instance.save())?;
+        Ok(InputSource::Recording(recorder))
+    } else {
+        Ok(InputSource::Live)
+    }
+}
+
+// Reads `--simulation-rate <steps per second>` from the command line
+fn simulation_rate() -> Result<Option<f32>> {
+    let Some(rate) = flag_value("--simulation-rate") else {
+        return Ok(None);
+    };
+    match rate.parse::<f32>() {
+        Ok(rate) if rate > 0.0 => Ok(Some(rate)),
+        _ => Err(anyhow::anyhow!(
+            "--simulation-rate must be a positive number, not '{rate}'"
+        )),
+    }
+}
+
 async fn run_frame<R: RunnableGameInstance>(
     instance: &R,
     screen: GameScreen,
+    input: &mut InputSource,
+    gamepads: &mut Gamepads,
     debug: Option<&mut DebugState>,
 ) {
-    let mouse = get_mouse_state();
-    let key = get_key_info();
+    let (mouse, key, gamepad, frame_time) = input.next_frame(gamepads);
 
     // Inserts hot reload keys to skip update frame
     // Save and dump state to console
@@ -98,7 +160,7 @@ async fn run_frame<R: RunnableGameInstance>(
     }
 
     if !skip_update {
-        instance.update_frame(mouse, key, screen.clone());
+        instance.update_frame(mouse, key, gamepad, screen.clone(), frame_time);
     }
     instance.render_frame(screen.clone());
 
@@ -128,8 +190,13 @@ async fn run_frame<R: RunnableGameInstance>(
 async fn run(font: Font, texture_cache: TextureCache) -> Result<()> {
     let screen = GameScreen::new(font, texture_cache);
     let instance = Game::new(&screen);
+    if let Some(rate) = simulation_rate()? {
+        instance.set_simulation_rate(rate);
+    }
+    let mut input = input_source(&instance)?;
+    let mut gamepads = Gamepads::default();
     loop {
-        run_frame(&instance, screen.clone(), None).await;
+        run_frame(&instance, screen.clone(), &mut input, &mut gamepads, None).await;
     }
 }
 
@@ -141,9 +208,15 @@ async fn run(font: Font, texture_cache: TextureCache) -> Result<()> {
     let screen = GameScreen::new(font, texture_cache);
 
     let mut instance = assembly.create_game_instance(screen.clone())?;
+    let rate = simulation_rate()?;
+    if let Some(rate) = rate {
+        instance.set_simulation_rate(rate)?;
+    }
 
     let file_watcher = crate::hotreload::watcher::FileWatcher::new(crate::hotreload::wasm_path()?)?;
     let mut debug_state = DebugState::default();
+    let mut input = input_source(&instance)?;
+    let mut gamepads = Gamepads::default();
 
     loop {
         if file_watcher.changed() {
@@ -154,9 +227,20 @@ async fn run(font: Font, texture_cache: TextureCache) -> Result<()> {
             if let Ok(save_data) = save_data {
                 let _ = instance.load(save_data);
             }
+            // The rate isn't part of the saved state, so the new instance needs it again
+            if let Some(rate) = rate {
+                instance.set_simulation_rate(rate)?;
+            }
         }
 
-        run_frame(&instance, screen.clone(), Some(&mut debug_state)).await;
+        run_frame(
+            &instance,
+            screen.clone(),
+            &mut input,
+            &mut gamepads,
+            Some(&mut debug_state),
+        )
+        .await;
     }
 }
 
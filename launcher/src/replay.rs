@@ -0,0 +1,292 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use anyhow::{anyhow, Result};
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::input::{
+    button_index, disconnected_gamepad, get_key_info, get_mouse_state, key_index, ClickInfo,
+    GamepadButton, GamepadInfo, Gamepads, Key, KeyboardInfo, MouseInfo, Position, ALL_BUTTONS,
+    ALL_KEYS,
+};
+
+const REPLAY_VERSION: u32 = 1;
+
+/// First line of a replay file. The game's saved state when recording
+/// began is stored so playback starts from the exact same point, seed included.
+#[derive(Debug, Serialize, Deserialize)]
+struct ReplayHeader {
+    version: u32,
+    state: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedClick {
+    pressed: bool,
+    released: bool,
+    down: bool,
+}
+
+impl From<&ClickInfo> for RecordedClick {
+    fn from(click: &ClickInfo) -> Self {
+        RecordedClick {
+            pressed: click.pressed,
+            released: click.released,
+            down: click.down,
+        }
+    }
+}
+
+impl From<&RecordedClick> for ClickInfo {
+    fn from(click: &RecordedClick) -> Self {
+        ClickInfo {
+            pressed: click.pressed,
+            released: click.released,
+            down: click.down,
+        }
+    }
+}
+
+/// A connected gamepad's state. Buttons are stored by their index in `ALL_BUTTONS`.
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedGamepad {
+    left_stick: (f32, f32),
+    right_stick: (f32, f32),
+    left_trigger: f32,
+    right_trigger: f32,
+    pressed: Vec<u8>,
+    released: Vec<u8>,
+    down: Vec<u8>,
+}
+
+fn encode_buttons(buttons: &[GamepadButton]) -> Vec<u8> {
+    buttons.iter().map(button_index).collect()
+}
+
+fn decode_buttons(buttons: &[u8]) -> Vec<GamepadButton> {
+    buttons
+        .iter()
+        .filter_map(|b| ALL_BUTTONS.get(*b as usize).cloned())
+        .collect()
+}
+
+impl From<&GamepadInfo> for RecordedGamepad {
+    fn from(gamepad: &GamepadInfo) -> Self {
+        RecordedGamepad {
+            left_stick: (gamepad.left_stick.x, gamepad.left_stick.y),
+            right_stick: (gamepad.right_stick.x, gamepad.right_stick.y),
+            left_trigger: gamepad.left_trigger,
+            right_trigger: gamepad.right_trigger,
+            pressed: encode_buttons(&gamepad.pressed),
+            released: encode_buttons(&gamepad.released),
+            down: encode_buttons(&gamepad.down),
+        }
+    }
+}
+
+impl From<&RecordedGamepad> for GamepadInfo {
+    fn from(gamepad: &RecordedGamepad) -> Self {
+        let (x, y) = gamepad.left_stick;
+        let left_stick = Position { x, y };
+        let (x, y) = gamepad.right_stick;
+        let right_stick = Position { x, y };
+        GamepadInfo {
+            connected: true,
+            left_stick,
+            right_stick,
+            left_trigger: gamepad.left_trigger,
+            right_trigger: gamepad.right_trigger,
+            pressed: decode_buttons(&gamepad.pressed),
+            released: decode_buttons(&gamepad.released),
+            down: decode_buttons(&gamepad.down),
+        }
+    }
+}
+
+/// One frame of input. Keys are stored by their index in `ALL_KEYS`.
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedFrame {
+    frame_time: f32,
+    mouse_x: f32,
+    mouse_y: f32,
+    left: RecordedClick,
+    right: RecordedClick,
+    middle: RecordedClick,
+    pressed: Vec<u8>,
+    released: Vec<u8>,
+    down: Vec<u8>,
+    /// Missing when no gamepad was connected, including in replays recorded before gamepad support
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    gamepad: Option<RecordedGamepad>,
+}
+
+fn encode_keys(keys: &[Key]) -> Vec<u8> {
+    keys.iter().map(key_index).collect()
+}
+
+fn decode_keys(keys: &[u8]) -> Vec<Key> {
+    keys.iter()
+        .filter_map(|k| ALL_KEYS.get(*k as usize).cloned())
+        .collect()
+}
+
+impl RecordedFrame {
+    fn capture(
+        mouse: &MouseInfo,
+        key: &KeyboardInfo,
+        gamepad: &GamepadInfo,
+        frame_time: f32,
+    ) -> Self {
+        RecordedFrame {
+            frame_time,
+            mouse_x: mouse.position.x,
+            mouse_y: mouse.position.y,
+            left: (&mouse.left).into(),
+            right: (&mouse.right).into(),
+            middle: (&mouse.middle).into(),
+            pressed: encode_keys(&key.pressed),
+            released: encode_keys(&key.released),
+            down: encode_keys(&key.down),
+            gamepad: gamepad.connected.then(|| gamepad.into()),
+        }
+    }
+
+    fn mouse(&self) -> MouseInfo {
+        MouseInfo {
+            position: Position {
+                x: self.mouse_x,
+                y: self.mouse_y,
+            },
+            left: (&self.left).into(),
+            right: (&self.right).into(),
+            middle: (&self.middle).into(),
+        }
+    }
+
+    fn keyboard(&self) -> KeyboardInfo {
+        KeyboardInfo {
+            pressed: decode_keys(&self.pressed),
+            released: decode_keys(&self.released),
+            down: decode_keys(&self.down),
+        }
+    }
+
+    fn gamepad(&self) -> GamepadInfo {
+        self.gamepad
+            .as_ref()
+            .map_or_else(disconnected_gamepad, |gamepad| gamepad.into())
+    }
+}
+
+pub struct InputRecorder {
+    writer: BufWriter<File>,
+}
+
+impl InputRecorder {
+    pub fn create(path: &Path, state: String) -> Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let header = ReplayHeader {
+            version: REPLAY_VERSION,
+            state,
+        };
+        serde_json::to_writer(&mut writer, &header)?;
+        writeln!(writer)?;
+        Ok(Self { writer })
+    }
+
+    fn record(&mut self, frame: &RecordedFrame) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, frame)?;
+        writeln!(self.writer)?;
+        // Flush every frame so a crash still leaves a usable replay
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+pub struct InputPlayback {
+    lines: std::io::Lines<BufReader<File>>,
+    initial_state: String,
+}
+
+impl InputPlayback {
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        let header = lines
+            .next()
+            .ok_or_else(|| anyhow!("Replay file is empty"))??;
+        let header: ReplayHeader = serde_json::from_str(&header)?;
+        if header.version != REPLAY_VERSION {
+            return Err(anyhow!(
+                "Unsupported replay version {} (expected {REPLAY_VERSION})",
+                header.version
+            ));
+        }
+
+        Ok(Self {
+            lines,
+            initial_state: header.state,
+        })
+    }
+
+    /// The saved game state to restore before playback begins
+    pub fn initial_state(&self) -> &str {
+        &self.initial_state
+    }
+
+    fn next_frame(&mut self) -> Option<RecordedFrame> {
+        let line = self.lines.next()?.ok()?;
+        match serde_json::from_str(&line) {
+            Ok(frame) => Some(frame),
+            Err(e) => {
+                println!("Unable to read replay frame: {e:?}");
+                None
+            }
+        }
+    }
+}
+
+/// Where each frame's input comes from
+pub enum InputSource {
+    Live,
+    Recording(InputRecorder),
+    Replay(InputPlayback),
+}
+
+impl InputSource {
+    pub fn next_frame(
+        &mut self,
+        gamepads: &mut Gamepads,
+    ) -> (MouseInfo, KeyboardInfo, GamepadInfo, f32) {
+        if let InputSource::Replay(playback) = self {
+            if let Some(frame) = playback.next_frame() {
+                return (
+                    frame.mouse(),
+                    frame.keyboard(),
+                    frame.gamepad(),
+                    frame.frame_time,
+                );
+            }
+            println!("Replay finished, switching to live input");
+            *self = InputSource::Live;
+        }
+
+        let mouse = get_mouse_state();
+        let key = get_key_info();
+        let gamepad = gamepads.get_gamepad_state();
+        let frame_time = get_frame_time();
+
+        if let InputSource::Recording(recorder) = self {
+            let frame = RecordedFrame::capture(&mouse, &key, &gamepad, frame_time);
+            if let Err(e) = recorder.record(&frame) {
+                println!("Unable to record input, stopping recording: {e:?}");
+                *self = InputSource::Live;
+            }
+        }
+
+        (mouse, key, gamepad, frame_time)
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use macroquad::{
     color::{Color, WHITE},
     math::Vec2,
-    shapes::{draw_circle, draw_line, draw_rectangle},
+    shapes::{draw_circle, draw_line, draw_rectangle, draw_triangle},
     text::{draw_text_ex, measure_text, Font, TextDimensions, TextParams},
     texture::{draw_texture_ex, DrawTextureParams, Texture2D},
     window::{screen_height, screen_width},
@@ -140,6 +140,26 @@ impl GameScreen {
         );
     }
 
+    pub fn draw_triangle(
+        &self,
+        first: Position,
+        second: Position,
+        third: Position,
+        color: GameColor,
+    ) {
+        draw_triangle(
+            Vec2::new(first.x, first.y),
+            Vec2::new(second.x, second.y),
+            Vec2::new(third.x, third.y),
+            Color {
+                r: color.r,
+                g: color.g,
+                b: color.b,
+                a: color.a,
+            },
+        );
+    }
+
     pub fn width(&self) -> f32 {
         screen_width()
     }
@@ -176,6 +196,10 @@ impl game::GameScreenInterface for GameScreen {
         self.draw_rectangle(position, size, color);
     }
 
+    fn draw_triangle(&self, first: Position, second: Position, third: Position, color: GameColor) {
+        self.draw_triangle(first, second, third, color);
+    }
+
     fn width(&self) -> f32 {
         screen_width()
     }
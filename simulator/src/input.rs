@@ -0,0 +1,159 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use game::{
+    caffeinated_gorilla::space::types::{ClickInfo, Key, Position},
+    exports::caffeinated_gorilla::space::game_api::{GamepadInfo, KeyboardInfo, MouseInfo},
+};
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg32;
+use serde::Deserialize;
+
+// How long the bot sticks with a direction before picking a new one
+const BOT_DECISION_FRAMES: u64 = 30;
+
+/// Keys held for a number of frames. Space is pressed every
+/// frame it is held, so the player fires as fast as allowed.
+/// `stick` pushes a gamepad's left stick to `[x, y]`, from -1 to 1 with y down.
+#[derive(Debug, Deserialize)]
+pub struct ScriptStep {
+    pub frames: u64,
+    #[serde(default)]
+    pub keys: Vec<String>,
+    #[serde(default)]
+    pub stick: Option<[f32; 2]>,
+}
+
+fn parse_key(name: &str) -> Result<Key> {
+    match name {
+        "Up" => Ok(Key::Up),
+        "Down" => Ok(Key::Down),
+        "Left" => Ok(Key::Left),
+        "Right" => Ok(Key::Right),
+        "Space" => Ok(Key::Space),
+        _ => Err(anyhow!(
+            "Unknown key '{name}', expected Up, Down, Left, Right or Space"
+        )),
+    }
+}
+
+/// What is held down for a frame
+#[derive(Debug, Clone, Default)]
+struct Held {
+    keys: Vec<Key>,
+    /// The gamepad's left stick, with no gamepad connected if `None`
+    stick: Option<Position>,
+}
+
+/// Input to hold and for how many frames, repeated once they run out
+#[derive(Debug, Clone)]
+pub struct Script {
+    steps: Vec<(u64, Held)>,
+}
+
+impl Script {
+    pub fn load(path: &Path) -> Result<Self> {
+        let steps: Vec<ScriptStep> = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let steps = steps
+            .into_iter()
+            .map(|step| {
+                let keys = step
+                    .keys
+                    .iter()
+                    .map(|k| parse_key(k))
+                    .collect::<Result<Vec<_>>>()?;
+                let stick = step.stick.map(|[x, y]| Position { x, y });
+                Ok((step.frames, Held { keys, stick }))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if steps.iter().map(|(frames, _)| frames).sum::<u64>() == 0 {
+            return Err(anyhow!("Script has no frames"));
+        }
+        Ok(Script { steps })
+    }
+
+    fn held(&self, frame: u64) -> Held {
+        let length: u64 = self.steps.iter().map(|(frames, _)| frames).sum();
+        let mut frame = frame % length;
+        for (frames, held) in &self.steps {
+            if frame < *frames {
+                return held.clone();
+            }
+            frame -= frames;
+        }
+        Held::default()
+    }
+}
+
+/// Where a run's input comes from
+pub enum InputSource {
+    /// A seeded bot that wanders at random while always firing
+    Bot {
+        rng: Pcg32,
+        keys: Vec<Key>,
+    },
+    Script(Script),
+}
+
+impl InputSource {
+    pub fn bot(seed: u64) -> Self {
+        InputSource::Bot {
+            rng: Pcg32::seed_from_u64(seed),
+            keys: vec![],
+        }
+    }
+
+    fn held(&mut self, frame: u64) -> Held {
+        match self {
+            InputSource::Bot { rng, keys } => {
+                if frame.is_multiple_of(BOT_DECISION_FRAMES) {
+                    let direction = [
+                        None,
+                        Some(Key::Up),
+                        Some(Key::Down),
+                        Some(Key::Left),
+                        Some(Key::Right),
+                    ][rng.gen_range(0..5)];
+                    *keys = direction.into_iter().chain([Key::Space]).collect();
+                }
+                Held {
+                    keys: keys.clone(),
+                    stick: None,
+                }
+            }
+            InputSource::Script(script) => script.held(frame),
+        }
+    }
+
+    pub fn next_frame(&mut self, frame: u64) -> (MouseInfo, KeyboardInfo, GamepadInfo) {
+        let Held { keys: down, stick } = self.held(frame);
+        let pressed = down.iter().filter(|k| **k == Key::Space).cloned().collect();
+
+        let click = ClickInfo {
+            pressed: false,
+            released: false,
+            down: false,
+        };
+        let mouse = MouseInfo {
+            position: Position { x: 0.0, y: 0.0 },
+            left: click,
+            right: click,
+            middle: click,
+        };
+        let key = KeyboardInfo {
+            pressed,
+            released: vec![],
+            down,
+        };
+        let gamepad = match stick {
+            Some(left_stick) => GamepadInfo {
+                connected: true,
+                left_stick,
+                ..Default::default()
+            },
+            None => GamepadInfo::default(),
+        };
+        (mouse, key, gamepad)
+    }
+}
@@ -0,0 +1,154 @@
+use std::{
+    path::PathBuf,
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+};
+
+use anyhow::{anyhow, Result};
+use game::{Game, RecordingScreen, RunSummary, Tuning};
+use serde::Serialize;
+
+mod input;
+use input::{InputSource, Script};
+
+// Matches the launcher's default window
+const SCREEN_WIDTH: f32 = 800.0;
+const SCREEN_HEIGHT: f32 = 600.0;
+// One simulation step per frame
+const FRAME_TIME: f32 = 1.0 / 60.0;
+
+struct Options {
+    runs: u64,
+    frames: u64,
+    seed: u64,
+    script: Option<Script>,
+    output: Option<PathBuf>,
+    tuning: Tuning,
+}
+
+const USAGE: &str =
+    "Usage: simulator [--runs N] [--frames N] [--seed N] [--script FILE] [--output FILE]
+                 [--movement-speed X] [--fire-rate X] [--rapid-fire-rate X]
+                 [--enemy-fire-rate X] [--spawn-rate X]";
+
+fn parse<T: FromStr>(flag: &str, value: Option<&String>) -> Result<T> {
+    value
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| anyhow!("{flag} needs a valid value\n{USAGE}"))
+}
+
+fn parse_options() -> Result<Options> {
+    let mut options = Options {
+        runs: 1,
+        frames: 60 * 60 * 5,
+        seed: 0,
+        script: None,
+        output: None,
+        tuning: Tuning::default(),
+    };
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let value = args.next();
+        match flag.as_str() {
+            "--runs" => options.runs = parse(flag, value)?,
+            "--frames" => options.frames = parse(flag, value)?,
+            "--seed" => options.seed = parse(flag, value)?,
+            "--script" => {
+                let path: PathBuf = parse(flag, value)?;
+                options.script = Some(Script::load(&path)?);
+            }
+            "--output" => options.output = Some(parse(flag, value)?),
+            "--movement-speed" => options.tuning.movement_speed = parse(flag, value)?,
+            "--fire-rate" => options.tuning.fire_rate = parse(flag, value)?,
+            "--rapid-fire-rate" => options.tuning.rapid_fire_rate = parse(flag, value)?,
+            "--enemy-fire-rate" => options.tuning.enemy_fire_rate = parse(flag, value)?,
+            "--spawn-rate" => options.tuning.spawn_rate = parse(flag, value)?,
+            _ => return Err(anyhow!("Unknown argument '{flag}'\n{USAGE}")),
+        }
+    }
+    Ok(options)
+}
+
+/// Plays a single game until the player dies or `frames` have passed
+fn simulate(options: &Options, seed: u64) -> RunSummary {
+    let screen = RecordingScreen::new(SCREEN_WIDTH, SCREEN_HEIGHT);
+    let game = Game::in_memory(&screen);
+    game.start_simulation(&screen, seed, options.tuning.clone());
+
+    let mut input = match &options.script {
+        Some(script) => InputSource::Script(script.clone()),
+        None => InputSource::bot(seed),
+    };
+    for frame in 0..options.frames {
+        let (mouse, key, gamepad) = input.next_frame(frame);
+        game.update_frame(mouse, key, gamepad, &screen, FRAME_TIME);
+        if game.is_game_over() {
+            break;
+        }
+    }
+    game.run_summary()
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    tuning: Tuning,
+    frames: u64,
+    average_score: f64,
+    average_frames_survived: f64,
+    deaths: u64,
+    runs: Vec<RunSummary>,
+}
+
+impl Report {
+    fn new(options: &Options, runs: Vec<RunSummary>) -> Self {
+        let count = runs.len().max(1) as f64;
+        Self {
+            tuning: options.tuning.clone(),
+            frames: options.frames,
+            average_score: runs.iter().map(|r| r.score as f64).sum::<f64>() / count,
+            average_frames_survived: runs.iter().map(|r| r.frames_survived as f64).sum::<f64>()
+                / count,
+            deaths: runs.iter().filter(|r| r.cause_of_death.is_some()).count() as u64,
+            runs,
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let options = parse_options()?;
+
+    // Runs are independent, so spread them over every core
+    let next_run = AtomicU64::new(0);
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let mut runs: Vec<RunSummary> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut runs = vec![];
+                    loop {
+                        let run = next_run.fetch_add(1, Ordering::Relaxed);
+                        if run >= options.runs {
+                            return runs;
+                        }
+                        runs.push(simulate(&options, options.seed.wrapping_add(run)));
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("Simulation thread panicked"))
+            .collect()
+    });
+    runs.sort_by_key(|r| r.seed);
+
+    let report = serde_json::to_string_pretty(&Report::new(&options, runs))?;
+    match &options.output {
+        Some(path) => std::fs::write(path, report)?,
+        None => println!("{report}"),
+    }
+    Ok(())
+}